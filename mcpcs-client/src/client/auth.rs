@@ -0,0 +1,93 @@
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+use crate::redact;
+
+/// 令牌到期前提前这么久重新获取
+const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+/// 认证命令最长运行时间
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 运行外部命令获取短期令牌 (`auth.type = "command"`)，缓存到快要过期为止
+pub(crate) struct TokenCommand {
+    command: String,
+    args: Vec<String>,
+    refresh_interval: Option<Duration>,
+    cached: Mutex<Option<Token>>,
+}
+
+struct Token {
+    value: String,
+    refresh_at: Option<Instant>,
+}
+
+impl TokenCommand {
+    pub fn new(command: &str, args: &[String], refresh_interval: Option<u64>) -> Self {
+        Self {
+            command: command.to_string(),
+            args: args.to_vec(),
+            refresh_interval: refresh_interval.map(Duration::from_secs),
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// 返回缓存的令牌；force 为 true (如收到 401) 或快到期时重新运行命令
+    pub async fn token(&self, force: bool) -> Result<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref() {
+            let fresh = token.refresh_at.is_none_or(|at| Instant::now() < at);
+            if fresh && !force {
+                return Ok(token.value.clone());
+            }
+        }
+        let token = self.run().await?;
+        let value = token.value.clone();
+        *cached = Some(token);
+        Ok(value)
+    }
+
+    /// 输出可以是令牌本身，也可以是带 `access_token`/`token` 和 `expires_in` 的 JSON
+    async fn run(&self) -> Result<Token> {
+        let output = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(COMMAND_TIMEOUT, output)
+            .await
+            .map_err(|_| anyhow::anyhow!("auth command '{}' did not finish within {}s", self.command, COMMAND_TIMEOUT.as_secs()))?
+            .with_context(|| format!("Failed to run auth command '{}'", self.command))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "auth command '{}' failed ({}): {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let (value, lifetime) = match serde_json::from_str::<serde_json::Value>(&stdout) {
+            Ok(serde_json::Value::Object(doc)) => {
+                let value = doc
+                    .get("access_token")
+                    .or_else(|| doc.get("token"))
+                    .and_then(|t| t.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("auth command '{}' printed JSON without 'access_token' or 'token'", self.command))?
+                    .to_string();
+                let lifetime = doc.get("expires_in").and_then(|e| e.as_u64()).map(Duration::from_secs);
+                (value, lifetime.or(self.refresh_interval))
+            }
+            _ => (stdout, self.refresh_interval),
+        };
+        if value.is_empty() {
+            anyhow::bail!("auth command '{}' printed no token", self.command);
+        }
+        redact::register(&value);
+        let refresh_at = lifetime.map(|lifetime| Instant::now() + lifetime.saturating_sub(REFRESH_MARGIN.min(lifetime / 2)));
+        Ok(Token { value, refresh_at })
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::{Prompt, Resource, ResourceTemplate, ServerCapabilities, Tool};
+use rmcp::service::{Peer, ServiceError};
+use rmcp::RoleClient;
+
+use super::events::ListKind;
+use super::{ClientError, ClientManager};
+
+/// 一类列表的缓存结果；获取失败时保留错误信息供列表命令显示
+#[derive(Debug, Clone)]
+pub struct Listed<T> {
+    pub items: Vec<T>,
+    pub error: Option<String>,
+}
+
+impl<T> Default for Listed<T> {
+    fn default() -> Self {
+        Self { items: Vec::new(), error: None }
+    }
+}
+
+impl<T> From<Result<Vec<T>, ServiceError>> for Listed<T> {
+    fn from(result: Result<Vec<T>, ServiceError>) -> Self {
+        match result {
+            Ok(items) => Self { items, error: None },
+            Err(e) => Self { items: Vec::new(), error: Some(e.to_string()) },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ServerCatalog {
+    pub tools: Listed<Tool>,
+    pub resources: Listed<Resource>,
+    pub templates: Listed<ResourceTemplate>,
+    pub prompts: Listed<Prompt>,
+}
+
+/// 每个服务器的工具、资源、资源模板和提示词
+/// 连接时填充，收到 list_changed 通知或 /refresh 时重新获取；名称解析和冲突检测都查这里
+#[derive(Default)]
+pub struct Catalog {
+    servers: RwLock<BTreeMap<String, ServerCatalog>>,
+    /// 填充时记下的连接，供 REPL 补全等拿不到 manager 的地方发请求
+    peers: RwLock<BTreeMap<String, Peer<RoleClient>>>,
+}
+
+impl Catalog {
+    pub async fn fill(&self, server: &str, peer: &Peer<RoleClient>) {
+        let (tools, resources, templates, prompts) = tokio::join!(
+            fetch_tools(peer),
+            fetch_resources(peer),
+            fetch_templates(peer),
+            fetch_prompts(peer)
+        );
+        self.servers.write().unwrap().insert(
+            server.to_string(),
+            ServerCatalog { tools, resources, templates, prompts },
+        );
+        self.peers.write().unwrap().insert(server.to_string(), peer.clone());
+    }
+
+    /// 只重新获取变化的那一类；资源列表变化时模板一并刷新
+    pub async fn refresh(&self, server: &str, peer: &Peer<RoleClient>, kind: ListKind) {
+        match kind {
+            ListKind::Tools => {
+                let tools = fetch_tools(peer).await;
+                self.update(server, |c| c.tools = tools);
+            }
+            ListKind::Resources => {
+                let (resources, templates) = tokio::join!(fetch_resources(peer), fetch_templates(peer));
+                self.update(server, |c| {
+                    c.resources = resources;
+                    c.templates = templates;
+                });
+            }
+            ListKind::Prompts => {
+                let prompts = fetch_prompts(peer).await;
+                self.update(server, |c| c.prompts = prompts);
+            }
+        }
+    }
+
+    fn update(&self, server: &str, apply: impl FnOnce(&mut ServerCatalog)) {
+        // 连接尚未完成时的通知忽略即可，fill 会拿到最新列表
+        if let Some(catalog) = self.servers.write().unwrap().get_mut(server) {
+            apply(catalog);
+        }
+    }
+
+    pub fn remove(&self, server: &str) {
+        self.servers.write().unwrap().remove(server);
+        self.peers.write().unwrap().remove(server);
+    }
+
+    pub fn peer(&self, server: &str) -> Option<Peer<RoleClient>> {
+        self.peers.read().unwrap().get(server).cloned()
+    }
+
+    pub fn get(&self, server: &str) -> ServerCatalog {
+        self.servers.read().unwrap().get(server).cloned().unwrap_or_default()
+    }
+
+    /// 在指定服务器 (None 为所有服务器) 的缓存中查找条目，按服务器名排序
+    pub fn find<T: Clone>(
+        &self,
+        server: Option<&str>,
+        list: impl Fn(&ServerCatalog) -> &Listed<T>,
+        matches: impl Fn(&T) -> bool,
+    ) -> Vec<(String, T)> {
+        self.servers
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| server.is_none_or(|s| s == name.as_str()))
+            .flat_map(|(name, catalog)| {
+                list(catalog)
+                    .items
+                    .iter()
+                    .filter(|item| matches(item))
+                    .map(|item| (name.clone(), item.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// 服务器未声明对应能力时不发请求，视为空列表
+pub(crate) fn supports(peer: &Peer<RoleClient>, capability: impl Fn(&ServerCapabilities) -> bool) -> bool {
+    peer.peer_info().is_none_or(|info| capability(&info.capabilities))
+}
+
+/// 以下各函数跟随 next_cursor 读完所有分页
+async fn fetch_tools(peer: &Peer<RoleClient>) -> Listed<Tool> {
+    if !supports(peer, |c| c.tools.is_some()) {
+        return Listed::default();
+    }
+    peer.list_all_tools().await.into()
+}
+
+async fn fetch_resources(peer: &Peer<RoleClient>) -> Listed<Resource> {
+    if !supports(peer, |c| c.resources.is_some()) {
+        return Listed::default();
+    }
+    peer.list_all_resources().await.into()
+}
+
+async fn fetch_templates(peer: &Peer<RoleClient>) -> Listed<ResourceTemplate> {
+    if !supports(peer, |c| c.resources.is_some()) {
+        return Listed::default();
+    }
+    peer.list_all_resource_templates().await.into()
+}
+
+async fn fetch_prompts(peer: &Peer<RoleClient>) -> Listed<Prompt> {
+    if !supports(peer, |c| c.prompts.is_some()) {
+        return Listed::default();
+    }
+    peer.list_all_prompts().await.into()
+}
+
+impl ClientManager {
+    /// 重新获取目录；server 为 None 时刷新所有服务器
+    pub async fn refresh_catalog(&self, server: Option<&str>) -> Result<()> {
+        if let Some(name) = server {
+            if !self.clients.contains_key(name) {
+                return Err(ClientError::ServerNotFound(name.to_string()).into());
+            }
+        }
+        for name in self.list_servers() {
+            if server.is_some_and(|s| s != name) {
+                continue;
+            }
+            self.context.catalog.fill(&name, &self.clients[&name]).await;
+            self.status(format!("{} {}", "Refreshed:".green(), name.cyan()));
+        }
+        Ok(())
+    }
+
+    /// 从目录查找结果中取出唯一的提供者：没有时 NotFound，多个时 Conflict
+    pub(crate) fn unique_match<T>(
+        &self,
+        kind: &'static str,
+        name: &str,
+        mut found: Vec<(String, T)>,
+    ) -> Result<(String, T)> {
+        match found.len() {
+            0 => Err(ClientError::NotFound {
+                kind,
+                name: name.to_string(),
+            }
+            .into()),
+            1 => Ok(found.remove(0)),
+            _ => Err(ClientError::Conflict {
+                kind,
+                name: name.to_string(),
+                servers: found.into_iter().map(|(server, _)| server).collect(),
+            }
+            .into()),
+        }
+    }
+}
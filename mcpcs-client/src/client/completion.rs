@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
+use rmcp::model::{
+    ArgumentInfo, CompleteRequestParam, CompletionContext, CompletionInfo, Prompt, Reference, ResourceTemplate,
+};
+use rmcp::service::Peer;
+use rmcp::RoleClient;
+use serde::Serialize;
+
+use super::catalog::supports;
+use super::handler::HandlerContext;
+use super::health::HealthBoard;
+use super::output::Render;
+use super::resources::UriTemplate;
+use super::{ClientError, ClientManager};
+
+/// Tab 补全等待服务器的最长时间，超时就当作没有候选
+const TAB_COMPLETION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 补全的对象：提示词参数或资源模板变量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompletionTarget {
+    Prompt,
+    Template,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompletionListing {
+    pub server: String,
+    pub target: CompletionTarget,
+    pub name: String,
+    pub argument: String,
+    pub value: String,
+    pub completion: CompletionInfo,
+}
+
+impl ClientManager {
+    pub async fn complete(
+        &self,
+        target: CompletionTarget,
+        spec: &str,
+        argument: &str,
+        value: &str,
+    ) -> Result<()> {
+        let listing = self.completion_listing(target, spec, argument, value).await?;
+        self.emit(&listing)
+    }
+
+    pub async fn completion_listing(
+        &self,
+        target: CompletionTarget,
+        spec: &str,
+        argument: &str,
+        value: &str,
+    ) -> Result<CompletionListing> {
+        let (server, name, reference) = match target {
+            CompletionTarget::Prompt => {
+                let details = self.prompt_details(spec)?;
+                (details.server, details.prompt.name.clone(), Reference::for_prompt(details.prompt.name))
+            }
+            CompletionTarget::Template => {
+                let (server, template) = self.resolve_template(spec)?;
+                (server, template.raw.name, Reference::for_resource(template.raw.uri_template))
+            }
+        };
+        let completion = request_completion(&self.clients[&server], &server, reference, argument, value, None).await?;
+        Ok(CompletionListing {
+            server,
+            target,
+            name,
+            argument: argument.to_string(),
+            value: value.to_string(),
+            completion,
+        })
+    }
+
+    /// 供 REPL 行编辑器在输入线程中同步查询补全
+    pub fn completion_source(&self) -> CompletionSource {
+        CompletionSource {
+            context: self.context.clone(),
+            health: self.health.clone(),
+            runtime: tokio::runtime::Handle::current(),
+        }
+    }
+}
+
+async fn request_completion(
+    peer: &Peer<RoleClient>,
+    server: &str,
+    reference: Reference,
+    argument: &str,
+    value: &str,
+    context: Option<CompletionContext>,
+) -> Result<CompletionInfo> {
+    if !supports(peer, |c| c.completions.is_some()) {
+        return Err(ClientError::Usage(format!("Server '{}' does not support argument completion", server)).into());
+    }
+    let result = peer
+        .complete(CompleteRequestParam {
+            r#ref: reference,
+            argument: ArgumentInfo {
+                name: argument.to_string(),
+                value: value.to_string(),
+            },
+            context,
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Error completing '{}' on {}: {}", argument, server, e))?;
+    Ok(result.completion)
+}
+
+/// Tab 补全时按名称补全的条目种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Tool,
+    Resource,
+    Template,
+    Prompt,
+}
+
+/// 不持有 manager 的补全入口：名称来自目录，参数补全通过目录中记下的连接请求
+#[derive(Clone)]
+pub struct CompletionSource {
+    context: Arc<HandlerContext>,
+    health: Arc<HealthBoard>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl CompletionSource {
+    /// 配置中的所有服务器，包括未连接的
+    pub fn servers(&self) -> Vec<String> {
+        self.health.servers()
+    }
+
+    /// 所有已连接服务器上的 (服务器, 名称)；资源为 URI
+    pub fn names(&self, kind: NameKind) -> Vec<(String, String)> {
+        let catalog = &self.context.catalog;
+        match kind {
+            NameKind::Tool => catalog.find(None, |c| &c.tools, |_| true).into_iter().map(|(s, t)| (s, t.name.to_string())).collect(),
+            NameKind::Resource => catalog.find(None, |c| &c.resources, |_| true).into_iter().map(|(s, r)| (s, r.raw.uri)).collect(),
+            NameKind::Template => catalog.find(None, |c| &c.templates, |_| true).into_iter().map(|(s, t)| (s, t.raw.name)).collect(),
+            NameKind::Prompt => catalog.find(None, |c| &c.prompts, |_| true).into_iter().map(|(s, p)| (s, p.name)).collect(),
+        }
+    }
+
+    /// 提示词的参数名或资源模板的变量名；名称不存在或有冲突时为空
+    pub fn arguments(&self, target: CompletionTarget, spec: &str) -> Vec<String> {
+        match target {
+            CompletionTarget::Prompt => self
+                .find_prompt(spec)
+                .map(|(_, prompt)| prompt.arguments.unwrap_or_default().into_iter().map(|arg| arg.name).collect())
+                .unwrap_or_default(),
+            CompletionTarget::Template => self
+                .find_template(spec)
+                .and_then(|(_, template)| UriTemplate::parse(&template.raw.uri_template).ok())
+                .map(|t| t.variables().into_iter().map(String::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 阻塞地向服务器请求参数补全，只能在 tokio 工作线程之外调用
+    pub fn complete(
+        &self,
+        target: CompletionTarget,
+        spec: &str,
+        argument: &str,
+        value: &str,
+        resolved: HashMap<String, String>,
+    ) -> Option<CompletionInfo> {
+        let (server, reference) = match target {
+            CompletionTarget::Prompt => {
+                let (server, prompt) = self.find_prompt(spec)?;
+                (server, Reference::for_prompt(prompt.name))
+            }
+            CompletionTarget::Template => {
+                let (server, template) = self.find_template(spec)?;
+                (server, Reference::for_resource(template.raw.uri_template))
+            }
+        };
+        let peer = self.context.catalog.peer(&server)?;
+        let context = (!resolved.is_empty()).then(|| CompletionContext::with_arguments(resolved));
+        let request = request_completion(&peer, &server, reference, argument, value, context);
+        self.runtime
+            .block_on(async { tokio::time::timeout(TAB_COMPLETION_TIMEOUT, request).await })
+            .ok()?
+            .ok()
+    }
+
+    fn find_prompt(&self, spec: &str) -> Option<(String, Prompt)> {
+        let (server, name) = super::parse_prompt_spec(spec);
+        let mut found = self.context.catalog.find(server, |c| &c.prompts, |p| p.name == name);
+        (found.len() == 1).then(|| found.remove(0))
+    }
+
+    fn find_template(&self, spec: &str) -> Option<(String, ResourceTemplate)> {
+        let (server, name) = super::parse_resource_spec(spec);
+        let mut found = self
+            .context
+            .catalog
+            .find(server, |c| &c.templates, |t| t.raw.name == name || t.raw.uri_template == name);
+        (found.len() == 1).then(|| found.remove(0))
+    }
+}
+
+impl Render for CompletionListing {
+    fn render_text(&self) -> Result<()> {
+        let values = &self.completion.values;
+        if values.is_empty() {
+            println!("{}", format!("No completions for '{}' on {}.", self.argument, self.server).yellow());
+            return Ok(());
+        }
+        for value in values {
+            println!("{} {}", "-".dimmed(), value.green());
+        }
+        if self.completion.has_more.unwrap_or(false) {
+            let more = match self.completion.total {
+                Some(total) => format!("... {} more ({} total)", (total as usize).saturating_sub(values.len()), total),
+                None => "... more available".to_string(),
+            };
+            println!("{}", more.dimmed());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use std::path::Path;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::config::{self, ConfigCheck, Dialect, McpConfig, Severity};
+use crate::redact::redact_json;
+use super::output::Render;
+use super::resources::render_diff;
+use super::{ClientError, ClientManager};
+
+impl ClientManager {
+    /// 检查所有配置文件；有错误 (不含警告) 时返回 InvalidConfig
+    pub fn check_config(&self) -> Result<()> {
+        let check = config::check_all()?;
+        self.emit(&check)?;
+        match check.errors() {
+            0 => Ok(()),
+            n => Err(ClientError::InvalidConfig(n).into()),
+        }
+    }
+}
+
+impl Render for ConfigCheck {
+    fn render_text(&self) -> Result<()> {
+        if self.files.is_empty() {
+            println!("{}", "No config files in ~/.mcpcsrs/mcps.".yellow());
+            return Ok(());
+        }
+        for file in &self.files {
+            let diagnostics: Vec<_> = self.diagnostics.iter().filter(|d| &d.file == file).collect();
+            if diagnostics.is_empty() {
+                println!("{} {}", "✓".green(), file);
+                continue;
+            }
+            if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+                println!("{} {}", "✗".red(), file);
+            } else {
+                println!("{} {}", "!".yellow(), file);
+            }
+            for d in diagnostics {
+                let label = match d.severity {
+                    Severity::Error => "error  ".red().bold(),
+                    Severity::Warning => "warning".yellow().bold(),
+                };
+                match &d.field {
+                    Some(field) => println!("  {} {}: {}", label, field.cyan(), d.message),
+                    None => println!("  {} {}", label, d.message),
+                }
+            }
+        }
+
+        let summary = format!(
+            "{} file(s), {} server(s): {} error(s), {} warning(s)",
+            self.files.len(),
+            self.servers,
+            self.errors(),
+            self.warnings()
+        );
+        if self.errors() > 0 {
+            println!("{}", summary.red());
+        } else if self.warnings() > 0 {
+            println!("{}", summary.yellow());
+        } else {
+            println!("{}", summary.green());
+        }
+        Ok(())
+    }
+}
+
+/// 导入结果：写入 (或 dry run 时将写入) 的文件和其中新增、替换的服务器
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub target: String,
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub notes: Vec<String>,
+    pub dry_run: bool,
+    #[serde(skip)]
+    before: String,
+    #[serde(skip)]
+    after: String,
+}
+
+impl ClientManager {
+    /// 把其他宿主格式的服务器列表转换后合并到 ~/.mcpcsrs/mcps/<name>.json，name 缺省为源文件名
+    pub fn import_config(&self, source: &str, name: Option<&str>, dry_run: bool) -> Result<()> {
+        let content = std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))?;
+        let document: Value = serde_json::from_str(&content)
+            .map_err(|e| ClientError::Usage(format!("{} is not valid JSON: {}", source, e)))?;
+        let mut notes = Vec::new();
+        let servers = config::import(&document, &mut notes).map_err(|e| ClientError::Usage(format!("{}: {:#}", source, e)))?;
+        if servers.is_empty() {
+            return Err(ClientError::Usage(format!("No MCP servers found in {}", source)).into());
+        }
+
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => Path::new(source)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .ok_or_else(|| ClientError::Usage(format!("Cannot derive a config name from '{}'", source)))?,
+        };
+        let mut target = config::config_dir()?.join(name);
+        if target.extension().is_none() {
+            target.set_extension("json");
+        }
+
+        // 合并到已有文件，保留其中的其他服务器和设置
+        let before = if target.exists() { std::fs::read_to_string(&target)? } else { String::new() };
+        let mut merged: Value = if before.trim().is_empty() {
+            json!({ "mcpServers": {} })
+        } else {
+            serde_json::from_str(&before).with_context(|| format!("Failed to parse {}", target.display()))?
+        };
+        let entries = merged
+            .as_object_mut()
+            .map(|root| root.entry("mcpServers").or_insert_with(|| json!({})))
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| anyhow::anyhow!("{} has no \"mcpServers\" object", target.display()))?;
+        let (mut added, mut replaced) = (Vec::new(), Vec::new());
+        for (server, server_conf) in servers {
+            let mut value = serde_json::to_value(&server_conf)?;
+            config::strip_nulls(&mut value);
+            match entries.insert(server.clone(), value) {
+                Some(_) => replaced.push(server),
+                None => added.push(server),
+            }
+        }
+        let after = format!("{}\n", serde_json::to_string_pretty(&merged)?);
+
+        // 导入后按 config check 的规则再检查一遍，例如命令不在 PATH 中
+        notes.extend(config::describe_errors(&target, &after));
+        if !dry_run {
+            std::fs::create_dir_all(config::config_dir()?)?;
+            std::fs::write(&target, &after)?;
+        }
+        self.emit(&ImportReport {
+            source: source.to_string(),
+            target: target.display().to_string(),
+            added,
+            replaced,
+            notes,
+            dry_run,
+            before,
+            after,
+        })
+    }
+
+    /// 把合并后的配置按指定格式写到文件或标准输出
+    pub fn export_config(&self, dialect: Dialect, output: Option<&str>) -> Result<()> {
+        let mut notes = Vec::new();
+        let document = config::export(&McpConfig::load()?, dialect, &mut notes)?;
+        for note in &notes {
+            eprintln!("{} {}", "Note:".yellow(), note);
+        }
+        let text = format!("{}\n", serde_json::to_string_pretty(&document)?);
+        match output {
+            Some(path) => {
+                std::fs::write(path, text).with_context(|| format!("Failed to write {}", path))?;
+                eprintln!("{} {}", "Exported config to".green(), path.cyan());
+            }
+            None => print!("{}", text),
+        }
+        Ok(())
+    }
+}
+
+/// 差异中敏感字段的值换成占位符 (`${VAR}` 引用保留)
+fn masked(content: &str) -> String {
+    match serde_json::from_str::<Value>(content) {
+        Ok(mut document) => {
+            redact_json(&mut document);
+            format!("{}\n", serde_json::to_string_pretty(&document).unwrap_or_default())
+        }
+        Err(_) => content.to_string(),
+    }
+}
+
+impl Render for ImportReport {
+    fn render_text(&self) -> Result<()> {
+        for note in &self.notes {
+            println!("{} {}", "Note:".yellow(), note);
+        }
+        let diff = render_diff(&masked(&self.before), &masked(&self.after));
+        if diff.is_empty() {
+            println!("{} {}", "No changes to".dimmed(), self.target);
+            return Ok(());
+        }
+        println!("{}", self.target.bold());
+        println!("{}", diff);
+        let summary = format!("{} added, {} replaced", self.added.len(), self.replaced.len());
+        if self.dry_run {
+            println!("{} {} ({})", "Dry run, nothing written:".yellow(), summary, "run without --dry-run to apply".dimmed());
+        } else {
+            println!("{} {} ({})", "Imported into".green(), self.target.cyan(), summary);
+        }
+        Ok(())
+    }
+}
@@ -1,144 +1,148 @@
-use crate::config::McpServerConfig;
+use std::sync::Arc;
+use crate::config::{AuthConfig, McpServerConfig};
+use crate::redact::redact;
 use anyhow::Result;
 use colored::Colorize;
 use rmcp::{
-    service::{RunningService, ServiceExt},
-    transport::{ConfigureCommandExt, TokioChildProcess, SseClientTransport, StreamableHttpClientTransport},
-    RoleClient,
+    service::ServiceExt,
+    transport::{ConfigureCommandExt, TokioChildProcess},
 };
-use std::sync::Arc;
 use tokio::process::Command;
 
-use super::ClientManager;
+use super::handler::McpcsClientHandler;
+use super::error::ClientError;
+use super::http::AuthClient;
+use super::oauth::OAuthTokens;
+use super::health::ServerState;
+use super::startup::ReloadSummary;
+use super::{ClientManager, ClientService};
 
 impl ClientManager {
+    /// 让运行中的服务器与配置一致：只启动、停止或重启配置有变化的条目
     pub async fn load_from_config(&mut self, config: &crate::config::McpConfig) -> Result<()> {
-        self.clients.clear();
+        if let Err(e) = self.apply_sampling_config(config.sampling.as_ref()) {
+            eprintln!("{} {}", "Invalid sampling config:".red(), redact(&format!("{:#}", e)));
+        }
+        if let Err(e) = self.apply_elicitation_config(config.elicitation.as_ref()) {
+            eprintln!("{} {}", "Invalid elicitation config:".red(), redact(&format!("{:#}", e)));
+        }
+        match self.apply_roots_config(config) {
+            Ok(changed) => {
+                for name in changed {
+                    self.notify_roots_changed(Some(&name)).await;
+                }
+            }
+            Err(e) => eprintln!("{} {}", "Invalid roots config:".red(), redact(&format!("{:#}", e))),
+        }
+
+        let mut summary = ReloadSummary::default();
+        let removed: Vec<String> = self
+            .configs
+            .keys()
+            .filter(|name| !config.mcp_servers.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in removed {
+            self.close(&name).await;
+            self.configs.remove(&name);
+            self.health.remove(&name);
+            summary.stopped.push(name);
+        }
+
+        let mut to_start = Vec::new();
         for (name, server_conf) in &config.mcp_servers {
-            match self.connect(server_conf).await {
-                Ok(client) => {
-                    self.clients.insert(name.clone(), Arc::new(client));
-                    println!("{} {}", "Connected:".green(), name.cyan());
+            let running = self.clients.contains_key(name) || self.failed.contains_key(name);
+            let changed = self.configs.get(name) != Some(server_conf);
+            self.configs.insert(name.clone(), server_conf.clone());
+            if server_conf.is_disabled() {
+                if running {
+                    self.close(name).await;
+                    summary.stopped.push(name.clone());
                 }
-                Err(e) => {
-                    eprintln!("{} '{}': {:#}", "Failed to connect:".red(), name, e);
+                self.health.stopped(name, ServerState::Disabled);
+                summary.disabled.push(name.clone());
+            } else if changed || !running {
+                if running {
+                    self.close(name).await;
                 }
+                to_start.push((name, server_conf));
+            } else {
+                summary.unchanged.push(name.clone());
             }
         }
-        Ok(())
+
+        summary.connected = self.connect_all(to_start).await.servers;
+        summary.stopped.sort();
+        summary.disabled.sort();
+        summary.unchanged.sort();
+        self.emit(&summary)
     }
+}
 
-    pub async fn connect(&self, config: &McpServerConfig) -> Result<RunningService<RoleClient, ()>> {
-        match config {
-            McpServerConfig::ChildProcess { command, args, env, .. }
-            | McpServerConfig::Legacy { command, args, env } => {
-                let cmd = Command::new(command);
-                let args = args.clone();
-                let env = env.clone();
+/// 按配置建立连接并完成初始化握手；重连时由后台任务直接调用
+pub(crate) async fn connect(config: &McpServerConfig, handler: McpcsClientHandler) -> Result<ClientService> {
+    let config = &config.resolved()?;
+    match config {
+        McpServerConfig::ChildProcess { command, args, env, .. }
+        | McpServerConfig::Legacy { command, args, env, .. } => {
+            let cmd = Command::new(command);
+            let args = args.clone();
+            let env = env.clone();
 
-                let transport = TokioChildProcess::new(cmd.configure(move |c| {
-                    c.args(&args);
-                    if let Some(e) = &env {
-                        c.envs(e);
-                    }
-                }))?;
+            let transport = TokioChildProcess::new(cmd.configure(move |c| {
+                c.args(&args);
+                if let Some(e) = &env {
+                    c.envs(e);
+                }
+            }))?;
 
-                let client = ().serve(transport).await?;
-                Ok(client)
-            }
-            McpServerConfig::Sse { url, auth_token, headers, .. } => {
-                use rmcp::transport::sse_client::{SseClientConfig, SseClientTransport};
-                
-                // 🚀 OpenAI方法：在reqwest客户端层面设置default headers  
-                let mut client_builder = reqwest::Client::builder()
-                    .timeout(std::time::Duration::from_secs(30));
-                
-                // 如果有自定义headers，设置为default headers
-                if let Some(headers) = headers {
-                    let mut header_map = reqwest::header::HeaderMap::new();
-                    
-                    for (name, value) in headers {
-                        match (name.parse::<reqwest::header::HeaderName>(), 
-                               reqwest::header::HeaderValue::from_str(&value)) {
-                            (Ok(header_name), Ok(header_value)) => {
-                                header_map.insert(header_name, header_value);
-                                eprintln!("🔍 Added SSE header: {} = {}", name, value);
-                            }
-                            _ => {
-                                eprintln!("⚠️ Invalid SSE header: {} = {}", name, value);
-                            }
-                        }
-                    }
-                    
-                    if !header_map.is_empty() {
-                        client_builder = client_builder.default_headers(header_map);
-                    }
+            let client = handler.serve(transport).await?;
+            Ok(client)
+        }
+        McpServerConfig::Sse { url, headers, network, .. } => {
+            use rmcp::transport::sse_client::{SseClientConfig, SseClientTransport};
+
+            let client = AuthClient::new(headers.as_ref(), config.auth()?.as_ref(), network, config.connect_timeout())?;
+            let sse_config = SseClientConfig {
+                sse_endpoint: url.clone().into(),
+                ..Default::default()
+            };
+            let transport = SseClientTransport::start_with_client(client, sse_config)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to start SSE transport: {}", e))?;
+
+            let client = handler.serve(transport).await?;
+            Ok(client)
+        }
+        McpServerConfig::Http { url, headers, stateless, network, .. } => {
+            use rmcp::transport::streamable_http_client::{StreamableHttpClientTransportConfig, StreamableHttpClientTransport};
+
+            let auth = config.auth()?;
+            let mut client = AuthClient::new(headers.as_ref(), auth.as_ref(), network, config.connect_timeout())?;
+            // 没有配置其他认证时使用保存的 OAuth 令牌，收到 401 质询后由前台连接发起授权
+            let server = handler.server_name().to_string();
+            let oauth = match auth {
+                None | Some(AuthConfig::OAuth(_)) => {
+                    Some(Arc::new(OAuthTokens::new(&server, url, network, config.connect_timeout())?))
                 }
-                
-                let http_client = client_builder.build()
-                    .map_err(|e| anyhow::anyhow!("Failed to build SSE client: {}", e))?;
-                
-                // 配置rmcp SSE transport  
-                let config = SseClientConfig {
-                    sse_endpoint: url.clone().try_into().unwrap(),
-                    ..Default::default()
-                };
-                
-                let transport = SseClientTransport::start_with_client(http_client, config)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to start SSE transport: {}", e))?;
-                
-                let client = ().serve(transport).await?;
-                Ok(client)
+                _ => None,
+            };
+            if let Some(oauth) = &oauth {
+                client = client.with_oauth(oauth.clone());
             }
-            McpServerConfig::Http { url, auth_token, headers, stateless, .. } => {
-                use rmcp::transport::streamable_http_client::{StreamableHttpClientTransportConfig, StreamableHttpClientTransport};
-                
-                // 🚀 OpenAI方法：在reqwest客户端层面设置default headers
-                let mut client_builder = reqwest::Client::builder()
-                    .timeout(std::time::Duration::from_secs(30));
-                
-                // 如果有自定义headers，设置为default headers
-                if let Some(headers) = headers {
-                    let mut header_map = reqwest::header::HeaderMap::new();
-                    
-                    for (name, value) in headers {
-                        match (name.parse::<reqwest::header::HeaderName>(), 
-                               reqwest::header::HeaderValue::from_str(&value)) {
-                            (Ok(header_name), Ok(header_value)) => {
-                                header_map.insert(header_name, header_value);
-                                eprintln!("🔍 Added HTTP header: {} = {}", name, value);
-                            }
-                            _ => {
-                                eprintln!("⚠️ Invalid HTTP header: {} = {}", name, value);
-                            }
-                        }
-                    }
-                    
-                    if !header_map.is_empty() {
-                        client_builder = client_builder.default_headers(header_map);
-                    }
-                }
-                
-                let http_client = client_builder.build()
-                    .map_err(|e| anyhow::anyhow!("Failed to build HTTP client: {}", e))?;
-                
-                // 配置rmcp transport
-                let mut config = StreamableHttpClientTransportConfig {
-                    uri: url.clone().into(),
-                    allow_stateless: stateless.unwrap_or(true),
-                    ..Default::default()
-                };
-                
-                // 如果有auth_token，设置为Authorization header
-                if let Some(token) = auth_token {
-                    config.auth_header = Some(format!("Bearer {}", token));
-                }
-                
-                // 使用配置好headers的客户端
-                let transport = StreamableHttpClientTransport::with_client(http_client, config);
-                let client = ().serve(transport).await?;
-                Ok(client)
+            let http_config = StreamableHttpClientTransportConfig {
+                uri: url.clone().into(),
+                allow_stateless: stateless.unwrap_or(true),
+                ..Default::default()
+            };
+            let transport = StreamableHttpClientTransport::with_client(client, http_config);
+
+            match handler.serve(transport).await {
+                Ok(client) => Ok(client),
+                Err(e) => match oauth.and_then(|oauth| oauth.challenge()) {
+                    Some(challenge) => Err(ClientError::AuthorizationRequired { server, challenge }.into()),
+                    None => Err(e.into()),
+                },
             }
         }
     }
@@ -0,0 +1,68 @@
+use anyhow::{Context, Result};
+use rmcp::model::{CreateElicitationRequestParam, CreateElicitationResult, ElicitationAction};
+use rmcp::ErrorData as McpError;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// 预设答复文件：单个对象或对象数组，按顺序取第一个 `match` 命中请求消息的条目
+///
+/// ```json
+/// [{ "match": "name", "action": "accept", "content": { "name": "Ada" } }, { "action": "decline" }]
+/// ```
+pub struct Fixture {
+    path: String,
+    entries: Vec<FixtureEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureEntry {
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+    /// 缺省为 accept
+    action: Option<ElicitationAction>,
+    content: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FixtureFile {
+    One(FixtureEntry),
+    Many(Vec<FixtureEntry>),
+}
+
+impl Fixture {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read elicitation fixture {}", path))?;
+        let entries = match serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse elicitation fixture {}", path))?
+        {
+            FixtureFile::One(entry) => vec![entry],
+            FixtureFile::Many(entries) => entries,
+        };
+        Ok(Self {
+            path: path.to_string(),
+            entries,
+        })
+    }
+
+    pub fn respond(&self, params: &CreateElicitationRequestParam) -> Result<CreateElicitationResult, McpError> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| match &entry.pattern {
+                Some(pattern) => params.message.contains(pattern.as_str()),
+                None => true,
+            })
+            .ok_or_else(|| {
+                McpError::internal_error(format!("No fixture in {} matches the request", self.path), None)
+            })?;
+
+        let action = entry.action.clone().unwrap_or(ElicitationAction::Accept);
+        let content = match action {
+            ElicitationAction::Accept => Some(entry.content.clone().unwrap_or_else(|| Value::Object(Default::default()))),
+            _ => None,
+        };
+        Ok(CreateElicitationResult { action, content })
+    }
+}
@@ -0,0 +1,109 @@
+mod fixture;
+
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::{CreateElicitationRequestParam, CreateElicitationResult, ElicitationAction};
+use rmcp::ErrorData as McpError;
+
+use crate::client::form::{self, FormOutcome};
+use crate::client::ClientManager;
+use crate::config::ElicitationConfig;
+use crate::console;
+use fixture::Fixture;
+
+/// 回答服务器 `elicitation/create` 请求的策略
+#[derive(Default)]
+pub enum ElicitationResponder {
+    #[default]
+    Interactive,
+    Decline,
+    Fixture(Fixture),
+}
+
+impl ElicitationResponder {
+    pub fn from_config(config: &ElicitationConfig) -> Result<Self> {
+        Ok(match config {
+            ElicitationConfig::Interactive => ElicitationResponder::Interactive,
+            ElicitationConfig::Decline => ElicitationResponder::Decline,
+            ElicitationConfig::Fixture { path } => ElicitationResponder::Fixture(Fixture::load(path)?),
+        })
+    }
+
+    fn mode(&self) -> &'static str {
+        match self {
+            ElicitationResponder::Interactive => "interactive",
+            ElicitationResponder::Decline => "decline",
+            ElicitationResponder::Fixture(_) => "fixture",
+        }
+    }
+
+    pub async fn respond(
+        &self,
+        server: &str,
+        params: CreateElicitationRequestParam,
+    ) -> Result<CreateElicitationResult, McpError> {
+        let console = console::global();
+        console.print_above(&format!(
+            "{} {} {}\n  {}",
+            format!("[{}]", server).cyan(),
+            "elicitation/create".magenta().bold(),
+            format!("(responder: {})", self.mode()).dimmed(),
+            params.message
+        ));
+
+        let result = match self {
+            ElicitationResponder::Interactive => {
+                let schema = serde_json::to_value(&params.requested_schema)
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                match form::fill_object(&schema).await {
+                    FormOutcome::Submitted(values) => CreateElicitationResult {
+                        action: ElicitationAction::Accept,
+                        content: Some(values.into()),
+                    },
+                    FormOutcome::Declined => declined(ElicitationAction::Decline),
+                    FormOutcome::Cancelled => declined(ElicitationAction::Cancel),
+                }
+            }
+            ElicitationResponder::Decline => declined(ElicitationAction::Decline),
+            ElicitationResponder::Fixture(fixture) => fixture.respond(&params)?,
+        };
+
+        let action = match result.action {
+            ElicitationAction::Accept => "accept".green(),
+            ElicitationAction::Decline => "decline".yellow(),
+            ElicitationAction::Cancel => "cancel".yellow(),
+        };
+        console.print_above(&format!("{} {}", "Elicitation answered:".dimmed(), action));
+        Ok(result)
+    }
+}
+
+fn declined(action: ElicitationAction) -> CreateElicitationResult {
+    CreateElicitationResult { action, content: None }
+}
+
+impl ClientManager {
+    /// 命令行指定的表单应答策略，优先于配置文件
+    pub fn pin_elicitation(&mut self, config: &ElicitationConfig) -> Result<()> {
+        self.set_elicitation(ElicitationResponder::from_config(config)?);
+        self.elicitation_pinned = true;
+        Ok(())
+    }
+
+    /// 应用配置文件中的设置，未配置时回到交互模式
+    pub fn apply_elicitation_config(&self, config: Option<&ElicitationConfig>) -> Result<()> {
+        if self.elicitation_pinned {
+            return Ok(());
+        }
+        let responder = match config {
+            Some(config) => ElicitationResponder::from_config(config)?,
+            None => ElicitationResponder::default(),
+        };
+        self.set_elicitation(responder);
+        Ok(())
+    }
+
+    fn set_elicitation(&self, responder: ElicitationResponder) {
+        *self.context.elicitation.write().unwrap() = std::sync::Arc::new(responder);
+    }
+}
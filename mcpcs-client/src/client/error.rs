@@ -0,0 +1,51 @@
+use thiserror::Error;
+
+/// 客户端操作失败的原因，非交互模式下用于决定进程退出码
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("Server not found: '{0}'")]
+    ServerNotFound(String),
+    #[error("{kind} not found: '{name}'")]
+    NotFound { kind: &'static str, name: String },
+    #[error("{kind} '{name}' exists in multiple servers: {}. Use <server>/{name} to specify.", servers.join(", "))]
+    Conflict {
+        kind: &'static str,
+        name: String,
+        servers: Vec<String>,
+    },
+    #[error("Tool '{0}' reported an error state")]
+    ToolFailed(String),
+    #[error("Invalid arguments for tool '{tool}':\n  {}", issues.join("\n  "))]
+    InvalidArguments { tool: String, issues: Vec<String> },
+    #[error("Config check found {0} error(s)")]
+    InvalidConfig(usize),
+    /// 服务器要求 OAuth 授权而没有可用的令牌；challenge 为服务器的 WWW-Authenticate 质询
+    #[error("Server '{server}' requires OAuth authorization")]
+    AuthorizationRequired { server: String, challenge: String },
+    #[error("Cancelled {0}")]
+    Cancelled(String),
+    #[error("Timed out {0}")]
+    TimedOut(String),
+}
+
+impl ClientError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ClientError::Usage(_) | ClientError::InvalidArguments { .. } | ClientError::InvalidConfig(_) => 2,
+            ClientError::ServerNotFound(_) | ClientError::NotFound { .. } => 3,
+            ClientError::Conflict { .. } => 4,
+            ClientError::ToolFailed(_) => 5,
+            ClientError::AuthorizationRequired { .. } | ClientError::TimedOut(_) => 1,
+            ClientError::Cancelled(_) => 130,
+        }
+    }
+}
+
+/// 0 成功, 1 一般错误 (连接/传输/IO/超时), 2 用法或配置错误, 3 未找到, 4 名称冲突, 5 工具返回错误, 130 被 Ctrl+C 取消
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    err.downcast_ref::<ClientError>()
+        .map(ClientError::exit_code)
+        .unwrap_or(1)
+}
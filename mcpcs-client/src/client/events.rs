@@ -0,0 +1,152 @@
+use colored::Colorize;
+use rmcp::model::{
+    CancelledNotificationParam, LoggingLevel, LoggingMessageNotificationParam, NumberOrString,
+    ProgressNotificationParam, ProgressToken,
+};
+use tokio::sync::mpsc;
+
+use super::health::ServerState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Tools,
+    Resources,
+    Prompts,
+}
+
+impl ListKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListKind::Tools => "tools",
+            ListKind::Resources => "resources",
+            ListKind::Prompts => "prompts",
+        }
+    }
+}
+
+/// 服务器主动推送给客户端的通知，由 handler 转发到 REPL 输出
+#[derive(Debug, Clone)]
+pub enum ClientEvent {
+    Log {
+        server: String,
+        params: LoggingMessageNotificationParam,
+    },
+    Progress {
+        server: String,
+        params: ProgressNotificationParam,
+    },
+    ResourceUpdated {
+        server: String,
+        uri: String,
+    },
+    ListChanged {
+        server: String,
+        kind: ListKind,
+    },
+    Cancelled {
+        server: String,
+        params: CancelledNotificationParam,
+    },
+    /// 健康检查发现连接状态变化
+    Connection {
+        server: String,
+        state: ServerState,
+        detail: String,
+    },
+}
+
+impl ClientEvent {
+    /// 单行彩色文本
+    pub fn render(&self) -> String {
+        match self {
+            ClientEvent::Log { server, params } => {
+                let level = match params.level {
+                    LoggingLevel::Debug => "debug".dimmed(),
+                    LoggingLevel::Info => "info".green(),
+                    LoggingLevel::Notice => "notice".cyan(),
+                    LoggingLevel::Warning => "warning".yellow(),
+                    LoggingLevel::Error => "error".red(),
+                    LoggingLevel::Critical => "critical".red().bold(),
+                    LoggingLevel::Alert => "alert".red().bold(),
+                    LoggingLevel::Emergency => "emergency".red().bold(),
+                };
+                let data = match &params.data {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                match &params.logger {
+                    Some(logger) => format!("[{}] {} {}: {}", server.cyan(), level, logger.dimmed(), data),
+                    None => format!("[{}] {} {}", server.cyan(), level, data),
+                }
+            }
+            ClientEvent::Progress { server, params } => {
+                let amount = match params.total {
+                    Some(total) if total > 0.0 => {
+                        format!("{:.0}% ({}/{})", params.progress / total * 100.0, params.progress, total)
+                    }
+                    _ => format!("{}", params.progress),
+                };
+                let message = params.message.as_deref().unwrap_or_default();
+                format!(
+                    "[{}] {} {} {} {}",
+                    server.cyan(),
+                    "progress".blue(),
+                    format_token(&params.progress_token).dimmed(),
+                    amount,
+                    message
+                )
+                .trim_end()
+                .to_string()
+            }
+            ClientEvent::ResourceUpdated { server, uri } => {
+                format!("[{}] {} {}", server.cyan(), "resource updated:".yellow(), uri)
+            }
+            ClientEvent::ListChanged { server, kind } => {
+                format!("[{}] {} {}", server.cyan(), kind.as_str().yellow(), "list changed".yellow())
+            }
+            ClientEvent::Cancelled { server, params } => {
+                let reason = params.reason.as_deref().unwrap_or("no reason given");
+                format!(
+                    "[{}] {} {} ({})",
+                    server.cyan(),
+                    "request cancelled by server:".yellow(),
+                    params.request_id,
+                    reason
+                )
+            }
+            ClientEvent::Connection { server, state, detail } => {
+                let detail = match state {
+                    ServerState::Connected => detail.green(),
+                    ServerState::Unresponsive => detail.yellow(),
+                    ServerState::Reconnecting | ServerState::Failed => detail.red(),
+                    ServerState::Disconnected | ServerState::Disabled => detail.dimmed(),
+                };
+                format!("[{}] {}", server.cyan(), detail)
+            }
+        }
+    }
+}
+
+pub fn format_token(token: &ProgressToken) -> String {
+    match &token.0 {
+        NumberOrString::Number(n) => format!("#{}", n),
+        NumberOrString::String(s) => format!("#{}", s),
+    }
+}
+
+/// handler 持有的发送端；接收端被丢弃后发送静默失败
+#[derive(Debug, Clone)]
+pub struct EventSink {
+    tx: mpsc::UnboundedSender<ClientEvent>,
+}
+
+impl EventSink {
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<ClientEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+
+    pub fn send(&self, event: ClientEvent) {
+        let _ = self.tx.send(event);
+    }
+}
@@ -0,0 +1,406 @@
+use std::future::Future;
+use std::pin::Pin;
+use colored::Colorize;
+use serde_json::{Map, Value};
+
+use crate::console;
+
+/// 按 JSON Schema 逐字段询问的结果
+pub enum FormOutcome {
+    Submitted(Map<String, Value>),
+    Declined,
+    Cancelled,
+}
+
+/// 单个字段的输入结果；`/decline`、`/cancel` 可在任意字段中止整个表单
+enum Field {
+    Value(Value),
+    Skip,
+    Decline,
+    Cancel,
+}
+
+type FieldFuture<'a> = Pin<Box<dyn Future<Output = Field> + Send + 'a>>;
+
+const DECLINE: &str = "/decline";
+const CANCEL: &str = "/cancel";
+
+/// 回答 elicitation：逐字段填写，最后选择接受、拒绝、取消或重新填写
+pub async fn fill_object(schema: &Value) -> FormOutcome {
+    run(schema, true).await
+}
+
+/// 为工具调用填写参数，最后确认发送；取消时返回 None
+pub async fn build_arguments(schema: &Value) -> Option<Map<String, Value>> {
+    match run(schema, false).await {
+        FormOutcome::Submitted(values) => Some(values),
+        FormOutcome::Declined | FormOutcome::Cancelled => None,
+    }
+}
+
+async fn run(schema: &Value, allow_decline: bool) -> FormOutcome {
+    let console = console::global();
+    if let Some(title) = schema.get("title").and_then(Value::as_str) {
+        console.print_above(&title.bold().to_string());
+    }
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        console.print_above(&description.dimmed().to_string());
+    }
+    let abort = if allow_decline {
+        format!("type {} or {} to abort", DECLINE, CANCEL)
+    } else {
+        format!("type {} to abort", CANCEL)
+    };
+    console.print_above(
+        &format!("Empty input keeps the default or skips optional fields; {}", abort)
+            .dimmed()
+            .to_string(),
+    );
+
+    let confirm = if allow_decline {
+        "accept / decline / cancel / edit [accept]>"
+    } else {
+        "send / cancel / edit [send]>"
+    };
+
+    loop {
+        let values = match ask_properties("", schema).await {
+            Field::Value(Value::Object(values)) => values,
+            Field::Decline if allow_decline => return FormOutcome::Declined,
+            Field::Value(_) | Field::Skip | Field::Decline | Field::Cancel => return FormOutcome::Cancelled,
+        };
+
+        console.print_above(&serde_json::to_string_pretty(&Value::Object(values.clone())).unwrap_or_default());
+        loop {
+            let answer = console.read_line(&format!("{} ", confirm.magenta())).await;
+            match answer.as_deref().map(str::trim) {
+                Some("" | "a" | "accept" | "s" | "send" | "y" | "yes") => return FormOutcome::Submitted(values),
+                Some("d" | "decline" | "n" | "no") if allow_decline => return FormOutcome::Declined,
+                Some("c" | "cancel") | None => return FormOutcome::Cancelled,
+                Some("e" | "edit") => break,
+                Some(_) => continue,
+            }
+        }
+    }
+}
+
+/// 逐个询问 object 的属性，嵌套字段以 `a.b` 形式显示路径
+fn ask_properties<'a>(prefix: &'a str, schema: &'a Value) -> FieldFuture<'a> {
+    Box::pin(async move {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut values = Map::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, prop) in properties {
+                let path = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", prefix, name)
+                };
+                match ask_field(&path, prop, required.contains(&name.as_str())).await {
+                    Field::Value(value) => {
+                        values.insert(name.clone(), value);
+                    }
+                    Field::Skip => {}
+                    abort => return abort,
+                }
+            }
+        }
+        Field::Value(Value::Object(values))
+    })
+}
+
+fn ask_field<'a>(path: &'a str, schema: &'a Value, required: bool) -> FieldFuture<'a> {
+    Box::pin(async move {
+        let type_name = schema_type(schema);
+        print_header(path, schema, type_name, required);
+
+        match type_name {
+            "object" if schema.get("properties").is_some() => ask_object(path, schema, required).await,
+            "array" => ask_array(path, schema, required).await,
+            _ => ask_value(path, schema, type_name, required).await,
+        }
+    })
+}
+
+fn print_header(path: &str, schema: &Value, type_name: &str, required: bool) {
+    let console = console::global();
+    let mut header = format!("{} {}", path.green().bold(), format!("({})", type_name).dimmed());
+    if required {
+        header.push_str(&" *".red().to_string());
+    }
+    if let Some(title) = schema.get("title").and_then(Value::as_str) {
+        header.push_str(&format!(" {}", title));
+    }
+    console.print_above(&header);
+    if let Some(description) = schema.get("description").and_then(Value::as_str) {
+        console.print_above(&format!("  {}", description.dimmed()));
+    }
+}
+
+async fn ask_object(path: &str, schema: &Value, required: bool) -> Field {
+    if !required {
+        match ask_yes_no(&format!("  Fill in {}? [y/N]> ", path)).await {
+            Field::Value(Value::Bool(true)) => {}
+            Field::Value(_) => return default_or_skip(schema),
+            abort => return abort,
+        }
+    }
+    ask_properties(path, schema).await
+}
+
+/// 基本类型的元素逐行输入、空行结束；对象或数组元素先确认是否继续添加
+async fn ask_array(path: &str, schema: &Value, required: bool) -> Field {
+    let console = console::global();
+    let default_items = Value::Object(Map::new());
+    let items = schema.get("items").unwrap_or(&default_items);
+    let item_type = schema_type(items);
+    let nested = matches!(item_type, "object" | "array");
+    let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let max = schema.get("maxItems").and_then(Value::as_u64).map(|m| m as usize);
+
+    if let Some(default) = schema.get("default") {
+        console.print_above(&format!("  {} {}", "default:".dimmed(), default));
+    }
+    if !nested {
+        print_options(items);
+        console.print_above(&format!("  {}", "One item per line, empty line to finish".dimmed()));
+    }
+
+    let mut values = Vec::new();
+    loop {
+        if max.is_some_and(|max| values.len() >= max) {
+            break;
+        }
+        let item_path = format!("{}[{}]", path, values.len());
+        let item = if nested {
+            let more = ask_yes_no(&format!("  Add an item to {}? [y/N]> ", path)).await;
+            match more {
+                Field::Value(Value::Bool(true)) => ask_field(&item_path, items, true).await,
+                Field::Value(_) => Field::Skip,
+                abort => abort,
+            }
+        } else {
+            ask_value(&item_path, items, item_type, false).await
+        };
+
+        match item {
+            Field::Value(value) => values.push(value),
+            Field::Skip if values.len() < min => {
+                console.print_above(&format!("  {}", format!("At least {} items required", min).yellow()));
+            }
+            Field::Skip => break,
+            abort => return abort,
+        }
+    }
+
+    if values.is_empty() && !required {
+        return default_or_skip(schema);
+    }
+    Field::Value(Value::Array(values))
+}
+
+/// 询问单个值；空输入使用默认值，可选字段则跳过
+async fn ask_value(path: &str, schema: &Value, type_name: &str, required: bool) -> Field {
+    let console = console::global();
+    let options = enum_options(schema);
+    // 数组元素的选项已在数组标题下列出
+    if !path.ends_with(']') {
+        print_options(schema);
+    }
+
+    let default = schema.get("default");
+    let prompt = match default {
+        Some(d) => format!("  {} [{}]> ", path, d),
+        None => format!("  {}> ", path),
+    };
+
+    loop {
+        let Some(input) = console.read_line(&prompt).await else {
+            return Field::Cancel;
+        };
+        let input = input.trim();
+        match input {
+            DECLINE => return Field::Decline,
+            CANCEL => return Field::Cancel,
+            "" => match default {
+                Some(d) if !path.ends_with(']') => return Field::Value(d.clone()),
+                _ if !required => return Field::Skip,
+                _ => {
+                    console.print_above(&format!("  {}", "This field is required".yellow()));
+                    continue;
+                }
+            },
+            _ => {}
+        }
+
+        let parsed = if options.is_empty() {
+            parse_value(input, schema, type_name)
+        } else {
+            pick_option(input, &options)
+        };
+        match parsed {
+            Ok(value) => return Field::Value(value),
+            Err(msg) => console.print_above(&format!("  {}", msg.yellow())),
+        }
+    }
+}
+
+async fn ask_yes_no(prompt: &str) -> Field {
+    let console = console::global();
+    loop {
+        let Some(input) = console.read_line(prompt).await else {
+            return Field::Cancel;
+        };
+        match input.trim().to_ascii_lowercase().as_str() {
+            DECLINE => return Field::Decline,
+            CANCEL => return Field::Cancel,
+            "y" | "yes" => return Field::Value(Value::Bool(true)),
+            "" | "n" | "no" => return Field::Value(Value::Bool(false)),
+            _ => continue,
+        }
+    }
+}
+
+fn default_or_skip(schema: &Value) -> Field {
+    match schema.get("default") {
+        Some(default) => Field::Value(default.clone()),
+        None => Field::Skip,
+    }
+}
+
+fn schema_type(schema: &Value) -> &str {
+    match schema.get("type") {
+        Some(Value::String(t)) => t,
+        // ["string", "null"] 之类的联合类型取第一个非 null
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null")
+            .unwrap_or("any"),
+        _ if schema.get("enum").is_some() || schema.get("oneOf").is_some() => "enum",
+        _ if schema.get("properties").is_some() => "object",
+        _ => "any",
+    }
+}
+
+fn print_options(schema: &Value) {
+    let console = console::global();
+    for (i, (value, label)) in enum_options(schema).iter().enumerate() {
+        console.print_above(&format!("  {}) {}", i + 1, label_for(value, label.as_deref())));
+    }
+}
+
+/// 可选值及其显示名称，支持 `enum` (+ `enumNames`) 和 `oneOf: [{const, title}]` 两种写法
+fn enum_options(schema: &Value) -> Vec<(Value, Option<String>)> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let names = schema.get("enumNames").and_then(Value::as_array);
+        return values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let name = names.and_then(|n| n.get(i)).and_then(Value::as_str).map(str::to_string);
+                (v.clone(), name)
+            })
+            .collect();
+    }
+    if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+        return variants
+            .iter()
+            .filter_map(|v| {
+                let value = v.get("const")?.clone();
+                let title = v.get("title").and_then(Value::as_str).map(str::to_string);
+                Some((value, title))
+            })
+            .collect();
+    }
+    Vec::new()
+}
+
+fn label_for(value: &Value, label: Option<&str>) -> String {
+    let value = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match label {
+        Some(label) if label != value => format!("{} {}", value, format!("({})", label).dimmed()),
+        _ => value,
+    }
+}
+
+/// 输入可以是序号，也可以是选项值本身
+fn pick_option(input: &str, options: &[(Value, Option<String>)]) -> Result<Value, String> {
+    if let Ok(index) = input.parse::<usize>() {
+        if (1..=options.len()).contains(&index) {
+            return Ok(options[index - 1].0.clone());
+        }
+    }
+    options
+        .iter()
+        .find(|(value, _)| match value {
+            Value::String(s) => s == input,
+            other => serde_json::from_str::<Value>(input).is_ok_and(|v| v == *other),
+        })
+        .map(|(value, _)| value.clone())
+        .ok_or_else(|| format!("Choose 1-{} or one of the listed values", options.len()))
+}
+
+fn parse_value(input: &str, schema: &Value, type_name: &str) -> Result<Value, String> {
+    match type_name {
+        "boolean" => match input.to_ascii_lowercase().as_str() {
+            "y" | "yes" | "true" | "1" => Ok(Value::Bool(true)),
+            "n" | "no" | "false" | "0" => Ok(Value::Bool(false)),
+            _ => Err("Expected yes or no".to_string()),
+        },
+        "integer" => {
+            let n: i64 = input.parse().map_err(|_| "Expected an integer".to_string())?;
+            check_range(n as f64, schema)?;
+            Ok(Value::from(n))
+        }
+        "number" => {
+            let n: f64 = input.parse().map_err(|_| "Expected a number".to_string())?;
+            check_range(n, schema)?;
+            serde_json::Number::from_f64(n)
+                .map(Value::Number)
+                .ok_or_else(|| "Expected a finite number".to_string())
+        }
+        "string" => {
+            let len = input.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    return Err(format!("Must be at least {} characters", min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    return Err(format!("Must be at most {} characters", max));
+                }
+            }
+            Ok(Value::String(input.to_string()))
+        }
+        // 无类型约束或无法逐字段填写的结构 (如无 properties 的 object)：按 JSON 解析，失败时作为字符串
+        "object" => serde_json::from_str::<Value>(input)
+            .ok()
+            .filter(Value::is_object)
+            .ok_or_else(|| "Expected a JSON object".to_string()),
+        _ => Ok(serde_json::from_str(input).unwrap_or_else(|_| Value::String(input.to_string()))),
+    }
+}
+
+fn check_range(n: f64, schema: &Value) -> Result<(), String> {
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        if n < min {
+            return Err(format!("Must be >= {}", min));
+        }
+    }
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        if n > max {
+            return Err(format!("Must be <= {}", max));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use std::sync::{Arc, RwLock};
+use rmcp::{
+    model::{
+        CancelledNotificationParam, ClientCapabilities, ClientInfo, CreateElicitationRequestParam,
+        CreateElicitationResult, CreateMessageRequestParam, CreateMessageResult, Implementation, ListRootsResult, LoggingMessageNotificationParam,
+        ProgressNotificationParam, ProtocolVersion, ResourceUpdatedNotificationParam,
+    },
+    service::{NotificationContext, RequestContext, RunningService},
+    ClientHandler, ErrorData as McpError, RoleClient,
+};
+
+use super::catalog::Catalog;
+use super::elicitation::ElicitationResponder;
+use super::events::{ClientEvent, EventSink, ListKind};
+use super::inflight::ProgressTracker;
+use super::resources::SubscriptionSet;
+use super::roots::RootSet;
+use super::sampling::SamplingResponder;
+
+pub type ClientService = RunningService<RoleClient, McpcsClientHandler>;
+
+/// 所有连接共享的客户端状态，manager 修改后对已连接的服务器立即生效
+pub struct HandlerContext {
+    pub events: EventSink,
+    pub sampling: RwLock<Arc<SamplingResponder>>,
+    pub roots: RwLock<RootSet>,
+    pub elicitation: RwLock<Arc<ElicitationResponder>>,
+    pub progress: ProgressTracker,
+    pub subscriptions: SubscriptionSet,
+    pub catalog: Catalog,
+}
+
+impl HandlerContext {
+    pub fn new(events: EventSink) -> Self {
+        Self {
+            events,
+            sampling: RwLock::new(Arc::default()),
+            roots: RwLock::new(RootSet::default()),
+            elicitation: RwLock::new(Arc::default()),
+            progress: ProgressTracker::default(),
+            subscriptions: SubscriptionSet::default(),
+            catalog: Catalog::default(),
+        }
+    }
+}
+
+/// 每个服务器连接一个 handler，把服务器推送的通知转发到事件流
+pub struct McpcsClientHandler {
+    server_name: String,
+    context: Arc<HandlerContext>,
+}
+
+impl McpcsClientHandler {
+    pub fn new(server_name: &str, context: Arc<HandlerContext>) -> Self {
+        Self {
+            server_name: server_name.to_string(),
+            context,
+        }
+    }
+
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    fn emit(&self, event: ClientEvent) {
+        self.context.events.send(event);
+    }
+
+    /// 先刷新目录再提示，之后的命令看到的就是新列表
+    async fn list_changed(&self, context: NotificationContext<RoleClient>, kind: ListKind) {
+        self.context.catalog.refresh(&self.server_name, &context.peer, kind).await;
+        self.emit(ClientEvent::ListChanged {
+            server: self.server_name.clone(),
+            kind,
+        });
+    }
+}
+
+impl ClientHandler for McpcsClientHandler {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ClientCapabilities::builder()
+                .enable_roots()
+                .enable_roots_list_changed()
+                .enable_sampling()
+                .enable_elicitation()
+                .build(),
+            client_info: Implementation {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                title: Some("mcpcs-client REPL".to_string()),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                icons: None,
+                website_url: None,
+            },
+        }
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, McpError> {
+        let responder = self.context.sampling.read().unwrap().clone();
+        responder.respond(&self.server_name, params).await
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, McpError> {
+        let responder = self.context.elicitation.read().unwrap().clone();
+        responder.respond(&self.server_name, request).await
+    }
+
+    async fn list_roots(&self, _context: RequestContext<RoleClient>) -> Result<ListRootsResult, McpError> {
+        let roots = self.context.roots.read().unwrap().for_server(&self.server_name);
+        Ok(ListRootsResult { roots })
+    }
+
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.emit(ClientEvent::Log { server: self.server_name.clone(), params });
+    }
+
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if !self.context.progress.update(&self.server_name, &params) {
+            self.emit(ClientEvent::Progress { server: self.server_name.clone(), params });
+        }
+    }
+
+    async fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+        context: NotificationContext<RoleClient>,
+    ) {
+        // 已订阅的资源重新读取并显示差异，其余只提示一行
+        let subscriptions = &self.context.subscriptions;
+        if !subscriptions.on_updated(&self.server_name, &context.peer, &params.uri).await {
+            self.emit(ClientEvent::ResourceUpdated {
+                server: self.server_name.clone(),
+                uri: params.uri,
+            });
+        }
+    }
+
+    async fn on_resource_list_changed(&self, context: NotificationContext<RoleClient>) {
+        self.list_changed(context, ListKind::Resources).await;
+    }
+
+    async fn on_tool_list_changed(&self, context: NotificationContext<RoleClient>) {
+        self.list_changed(context, ListKind::Tools).await;
+    }
+
+    async fn on_prompt_list_changed(&self, context: NotificationContext<RoleClient>) {
+        self.list_changed(context, ListKind::Prompts).await;
+    }
+
+    async fn on_cancelled(
+        &self,
+        params: CancelledNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.emit(ClientEvent::Cancelled { server: self.server_name.clone(), params });
+    }
+}
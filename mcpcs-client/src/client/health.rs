@@ -0,0 +1,407 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::{ClientRequest, PingRequest};
+use rmcp::service::{Peer, PeerRequestOptions};
+use rmcp::RoleClient;
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::config::McpServerConfig;
+use crate::redact::redact;
+use super::events::ClientEvent;
+use super::handler::HandlerContext;
+use super::output::Render;
+use super::startup::establish;
+use super::{ClientManager, ClientService};
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// 连续这么多次 ping 失败后认为连接已断开
+const MAX_MISSED_PINGS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ServerState {
+    Connected,
+    /// ping 没有响应，但还没达到判定断开的次数
+    Unresponsive,
+    /// 连接已断开，正在按退避间隔重连
+    Reconnecting,
+    /// 连接失败且没有后台重连 (非交互模式)
+    Failed,
+    /// 用 /disconnect 断开
+    Disconnected,
+    /// 配置中标记了 disabled
+    Disabled,
+}
+
+impl ServerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerState::Connected => "connected",
+            ServerState::Unresponsive => "unresponsive",
+            ServerState::Reconnecting => "reconnecting",
+            ServerState::Failed => "failed",
+            ServerState::Disconnected => "disconnected",
+            ServerState::Disabled => "disabled",
+        }
+    }
+}
+
+struct ServerHealth {
+    state: ServerState,
+    latency: Option<Duration>,
+    connected_at: Option<Instant>,
+    restarts: u32,
+    error: Option<String>,
+    next_attempt: Option<Instant>,
+}
+
+/// 各服务器的连接状态，由后台健康检查任务更新，/status 读取
+#[derive(Default)]
+pub struct HealthBoard {
+    servers: Mutex<BTreeMap<String, ServerHealth>>,
+}
+
+impl HealthBoard {
+    pub fn connected(&self, server: &str) {
+        self.servers.lock().unwrap().insert(
+            server.to_string(),
+            ServerHealth {
+                state: ServerState::Connected,
+                latency: None,
+                connected_at: Some(Instant::now()),
+                restarts: 0,
+                error: None,
+                next_attempt: None,
+            },
+        );
+    }
+
+    pub fn failed(&self, server: &str, state: ServerState, error: String) {
+        self.servers.lock().unwrap().insert(
+            server.to_string(),
+            ServerHealth {
+                state,
+                latency: None,
+                connected_at: None,
+                restarts: 0,
+                error: Some(error),
+                next_attempt: None,
+            },
+        );
+    }
+
+    /// 停止运行的服务器 (断开或禁用)，保留重启次数
+    pub fn stopped(&self, server: &str, state: ServerState) {
+        let mut servers = self.servers.lock().unwrap();
+        let restarts = servers.get(server).map_or(0, |h| h.restarts);
+        servers.insert(
+            server.to_string(),
+            ServerHealth {
+                state,
+                latency: None,
+                connected_at: None,
+                restarts,
+                error: None,
+                next_attempt: None,
+            },
+        );
+    }
+
+    pub fn remove(&self, server: &str) {
+        self.servers.lock().unwrap().remove(server);
+    }
+
+    /// 所有已知的服务器，包括断开、失败和被禁用的
+    pub fn servers(&self) -> Vec<String> {
+        self.servers.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub fn restarts(&self, server: &str) -> u32 {
+        self.servers.lock().unwrap().get(server).map_or(0, |h| h.restarts)
+    }
+
+    pub fn set_restarts(&self, server: &str, restarts: u32) {
+        self.update(server, |h| h.restarts = restarts);
+    }
+
+    fn update(&self, server: &str, apply: impl FnOnce(&mut ServerHealth)) {
+        if let Some(health) = self.servers.lock().unwrap().get_mut(server) {
+            apply(health);
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerStatus {
+    pub server: String,
+    pub state: ServerState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime_secs: Option<u64>,
+    pub restarts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 距下一次重连尝试的秒数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusListing {
+    pub servers: Vec<ServerStatus>,
+}
+
+/// 后台重连成功后交给 manager 的新连接，generation 用于丢弃已被取代的监视任务的结果
+pub struct Reconnected {
+    server: String,
+    generation: u64,
+    client: ClientService,
+}
+
+impl ClientManager {
+    /// 为已知的服务器启动健康检查，之后新连接的服务器也会自动开始
+    pub fn enable_health_checks(&mut self) {
+        self.health_checks = true;
+        let servers: Vec<(String, McpServerConfig)> =
+            self.configs.iter().map(|(name, config)| (name.clone(), config.clone())).collect();
+        for (name, config) in servers {
+            self.start_monitor(&name, config);
+        }
+    }
+
+    /// 启动 (或替换) 某个服务器的监视任务：已连接时定期 ping，未连接时按退避间隔重连
+    pub(crate) fn start_monitor(&mut self, name: &str, config: McpServerConfig) {
+        if !self.health_checks {
+            return;
+        }
+        self.stop_monitor(name);
+        self.monitor_generation += 1;
+        let monitor = Monitor {
+            server: name.to_string(),
+            generation: self.monitor_generation,
+            config,
+            context: self.context.clone(),
+            health: self.health.clone(),
+            reconnected: self.reconnected_tx.clone(),
+        };
+        let peer = self.clients.get(name).map(|client| client.peer().clone());
+        let handle = tokio::spawn(monitor.run(peer));
+        self.monitors.insert(name.to_string(), (self.monitor_generation, handle));
+    }
+
+    pub(crate) fn stop_monitor(&mut self, name: &str) {
+        if let Some((_, handle)) = self.monitors.remove(name) {
+            handle.abort();
+        }
+    }
+
+    pub(crate) fn stop_monitors(&mut self) {
+        for (_, (_, handle)) in self.monitors.drain() {
+            handle.abort();
+        }
+    }
+
+    /// 接收后台重连成功的连接，替换掉已断开的旧连接；每条命令执行前调用
+    pub fn apply_reconnects(&mut self) {
+        while let Ok(Reconnected { server, generation, client }) = self.reconnected_rx.try_recv() {
+            let current = self.monitors.get(&server).map(|(g, _)| *g);
+            if current == Some(generation) {
+                self.failed.remove(&server);
+                self.clients.insert(server, Arc::new(client));
+            }
+        }
+    }
+
+    pub fn status_listing(&self) -> StatusListing {
+        let servers = self.health.servers.lock().unwrap();
+        StatusListing {
+            servers: servers
+                .iter()
+                .map(|(name, health)| ServerStatus {
+                    server: name.clone(),
+                    state: health.state,
+                    latency_ms: health.latency.map(|l| l.as_millis()),
+                    uptime_secs: match health.state {
+                        ServerState::Connected | ServerState::Unresponsive => {
+                            health.connected_at.map(|t| t.elapsed().as_secs())
+                        }
+                        _ => None,
+                    },
+                    restarts: health.restarts,
+                    error: health.error.clone(),
+                    retry_in_secs: health
+                        .next_attempt
+                        .map(|t| t.saturating_duration_since(Instant::now()).as_secs()),
+                })
+                .collect(),
+        }
+    }
+}
+
+struct Monitor {
+    server: String,
+    generation: u64,
+    config: McpServerConfig,
+    context: Arc<HandlerContext>,
+    health: Arc<HealthBoard>,
+    reconnected: UnboundedSender<Reconnected>,
+}
+
+impl Monitor {
+    async fn run(self, mut peer: Option<Peer<RoleClient>>) {
+        loop {
+            if let Some(peer) = peer.take() {
+                self.watch(peer).await;
+            }
+            peer = Some(self.reconnect().await);
+        }
+    }
+
+    /// 定期 ping，直到判定连接断开
+    async fn watch(&self, peer: Peer<RoleClient>) {
+        let mut missed = 0;
+        loop {
+            match ping(&peer).await {
+                Ok(latency) => {
+                    missed = 0;
+                    self.health.update(&self.server, |h| {
+                        if h.state == ServerState::Unresponsive {
+                            h.state = ServerState::Connected;
+                            h.error = None;
+                        }
+                        h.latency = Some(latency);
+                    });
+                }
+                Err(e) => {
+                    missed += 1;
+                    let error = redact(&format!("{:#}", e));
+                    if peer.is_transport_closed() || missed >= MAX_MISSED_PINGS {
+                        self.health.update(&self.server, |h| {
+                            h.state = ServerState::Reconnecting;
+                            h.error = Some(error.clone());
+                        });
+                        self.emit(ServerState::Reconnecting, format!("connection lost: {}", error));
+                        return;
+                    }
+                    self.health.update(&self.server, |h| {
+                        h.state = ServerState::Unresponsive;
+                        h.error = Some(error.clone());
+                    });
+                    self.emit(ServerState::Unresponsive, format!("ping failed ({}/{}): {}", missed, MAX_MISSED_PINGS, error));
+                }
+            }
+            tokio::time::sleep(PING_INTERVAL).await;
+        }
+    }
+
+    /// 按指数退避重连，直到成功；每次失败只记录在状态里，不逐次提示
+    async fn reconnect(&self) -> Peer<RoleClient> {
+        let mut delay = INITIAL_BACKOFF;
+        loop {
+            self.health.update(&self.server, |h| h.next_attempt = Some(Instant::now() + delay));
+            tokio::time::sleep(delay).await;
+            match establish(&self.server, &self.config, &self.context).await {
+                Ok(client) => {
+                    let peer = client.peer().clone();
+                    self.context.subscriptions.restore(&self.server, &peer).await;
+                    let mut restarts = 0;
+                    self.health.update(&self.server, |h| {
+                        h.state = ServerState::Connected;
+                        h.latency = None;
+                        h.connected_at = Some(Instant::now());
+                        h.restarts += 1;
+                        h.error = None;
+                        h.next_attempt = None;
+                        restarts = h.restarts;
+                    });
+                    let _ = self.reconnected.send(Reconnected {
+                        server: self.server.clone(),
+                        generation: self.generation,
+                        client,
+                    });
+                    self.emit(ServerState::Connected, format!("reconnected (restart #{})", restarts));
+                    return peer;
+                }
+                Err(e) => {
+                    delay = (delay * 2).min(MAX_BACKOFF);
+                    let error = redact(&format!("{:#}", e));
+                    self.health.update(&self.server, |h| h.error = Some(error));
+                }
+            }
+        }
+    }
+
+    fn emit(&self, state: ServerState, detail: String) {
+        self.context.events.send(ClientEvent::Connection {
+            server: self.server.clone(),
+            state,
+            detail,
+        });
+    }
+}
+
+async fn ping(peer: &Peer<RoleClient>) -> Result<Duration> {
+    let started = Instant::now();
+    let options = PeerRequestOptions {
+        timeout: Some(PING_TIMEOUT),
+        meta: None,
+    };
+    peer.send_request_with_option(ClientRequest::PingRequest(PingRequest::default()), options)
+        .await?
+        .await_response()
+        .await?;
+    Ok(started.elapsed())
+}
+
+impl Render for StatusListing {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
+            println!("{}", "No servers configured.".yellow());
+            return Ok(());
+        }
+        let width = self.servers.iter().map(|s| s.server.len()).max().unwrap_or(0).max("Server".len());
+        println!(
+            "{}",
+            format!("{:<width$}  {:<12}  {:>8}  {:>8}  {:>8}", "Server", "State", "Latency", "Uptime", "Restarts").bold()
+        );
+        for status in &self.servers {
+            let state = format!("{:<12}", status.state.as_str());
+            let state = match status.state {
+                ServerState::Connected => state.green(),
+                ServerState::Unresponsive => state.yellow(),
+                ServerState::Reconnecting | ServerState::Failed => state.red(),
+                ServerState::Disconnected | ServerState::Disabled => state.dimmed(),
+            };
+            let latency = status.latency_ms.map(|ms| format!("{}ms", ms)).unwrap_or_else(|| "-".to_string());
+            let uptime = status.uptime_secs.map(format_uptime).unwrap_or_else(|| "-".to_string());
+            let server = format!("{:<width$}", status.server);
+            let line = format!("{}  {}  {:>8}  {:>8}  {:>8}", server.cyan(), state, latency, uptime, status.restarts);
+            let detail = match (&status.error, status.retry_in_secs) {
+                (Some(error), Some(secs)) => format!("{} (next attempt in {}s)", error, secs),
+                (Some(error), None) => error.clone(),
+                (None, _) => {
+                    println!("{}", line);
+                    continue;
+                }
+            };
+            println!("{}  {}", line, detail.dimmed());
+        }
+        Ok(())
+    }
+}
+
+fn format_uptime(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{Context, Result};
+use base64::Engine;
+use futures::stream::BoxStream;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::StatusCode;
+use rmcp::model::{ClientJsonRpcMessage, ClientRequest, JsonRpcMessage};
+use rmcp::transport::sse_client::{SseClient, SseTransportError};
+use rmcp::transport::streamable_http_client::{StreamableHttpClient, StreamableHttpError, StreamableHttpPostResponse};
+use sse_stream::{Error as SseError, Sse};
+
+use crate::config::{AuthConfig, NetworkConfig, DEFAULT_REQUEST_TIMEOUT};
+use super::auth::TokenCommand;
+use super::oauth::OAuthTokens;
+
+/// SSE 和 HTTP 传输共用的客户端：自定义请求头和静态认证作为默认请求头，
+/// 命令或 OAuth 获取的令牌在每次请求时附上，收到 401 时刷新后重试一次
+#[derive(Clone)]
+pub(crate) struct AuthClient {
+    /// 普通请求，受 timeout 限制
+    http: reqwest::Client,
+    /// 事件流和工具调用：不设总时长，工具调用的时限由调用方控制
+    streaming: reqwest::Client,
+    tokens: Option<Arc<TokenSource>>,
+}
+
+/// 每次请求时获取的令牌
+enum TokenSource {
+    Command(TokenCommand),
+    OAuth(Arc<OAuthTokens>),
+}
+
+impl TokenSource {
+    async fn token(&self, force: bool) -> Result<Option<String>> {
+        match self {
+            TokenSource::Command(command) => command.token(force).await.map(Some),
+            TokenSource::OAuth(oauth) => oauth.token(force).await,
+        }
+    }
+
+    fn rejected(&self, challenge: &str) {
+        if let TokenSource::OAuth(oauth) = self {
+            oauth.rejected(challenge);
+        }
+    }
+}
+
+impl AuthClient {
+    pub fn new(
+        headers: Option<&HashMap<String, String>>,
+        auth: Option<&AuthConfig>,
+        network: &NetworkConfig,
+        connect_timeout: Duration,
+    ) -> Result<Self> {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers.into_iter().flatten() {
+            insert_header(&mut header_map, name, value)?;
+        }
+        let mut tokens = None;
+        match auth {
+            None => {}
+            Some(AuthConfig::Bearer { token }) => insert_header(&mut header_map, AUTHORIZATION.as_str(), &format!("Bearer {}", token))?,
+            Some(AuthConfig::Basic { username, password }) => {
+                let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+                insert_header(&mut header_map, AUTHORIZATION.as_str(), &format!("Basic {}", credentials))?;
+            }
+            Some(AuthConfig::Header { name, value }) => insert_header(&mut header_map, name, value)?,
+            Some(AuthConfig::Command { command, args, refresh_interval }) => {
+                tokens = Some(Arc::new(TokenSource::Command(TokenCommand::new(command, args, *refresh_interval))));
+            }
+            // OAuth 令牌由调用方通过 with_oauth 提供
+            Some(AuthConfig::OAuth(_)) => {}
+        }
+
+        let timeout = network.timeout.map(Duration::from_secs).unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+        let http = client_builder(header_map.clone(), network, connect_timeout)?
+            .timeout(timeout)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build HTTP client: {}", e))?;
+        let streaming = client_builder(header_map, network, connect_timeout)?
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build HTTP client: {}", e))?;
+        Ok(Self { http, streaming, tokens })
+    }
+
+    /// 使用 OAuth 令牌 (已保存的授权)，替代其他按请求获取的令牌
+    pub fn with_oauth(mut self, oauth: Arc<OAuthTokens>) -> Self {
+        self.tokens = Some(Arc::new(TokenSource::OAuth(oauth)));
+        self
+    }
+
+    /// 带上当前令牌调用；令牌被拒绝时重新获取并再试一次
+    /// unauthorized 对 401 返回服务器的 WWW-Authenticate 质询 (可能为空)
+    async fn with_token<T, E, Fut>(
+        &self,
+        auth_token: Option<String>,
+        call: impl Fn(Option<String>) -> Fut,
+        unauthorized: impl Fn(&E) -> Option<String>,
+        wrap: impl Fn(std::io::Error) -> E,
+    ) -> Result<T, E>
+    where
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let Some(tokens) = &self.tokens else {
+            return call(auth_token).await;
+        };
+        let token_error = |e: anyhow::Error| wrap(std::io::Error::other(format!("{:#}", e)));
+        let token = tokens.token(false).await.map_err(token_error)?;
+        let had_token = token.is_some();
+        let result = match call(token.or(auth_token.clone())).await {
+            Err(e) if had_token && unauthorized(&e).is_some() => {
+                log::debug!("token rejected, fetching a new one");
+                match tokens.token(true).await.map_err(token_error)? {
+                    Some(token) => call(Some(token)).await,
+                    None => Err(e),
+                }
+            }
+            other => other,
+        };
+        if let Err(e) = &result {
+            if let Some(challenge) = unauthorized(e) {
+                tokens.rejected(&challenge);
+            }
+        }
+        result
+    }
+}
+
+/// 按网络设置准备 HTTP 客户端：请求头、连接超时、代理和 TLS；总超时由调用方决定
+pub(crate) fn client_builder(headers: HeaderMap, network: &NetworkConfig, connect_timeout: Duration) -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
+        .default_headers(headers)
+        .connect_timeout(connect_timeout);
+    if let Some(proxy) = &network.proxy {
+        let proxy = reqwest::Proxy::all(proxy).map_err(|e| anyhow::anyhow!("Invalid proxy '{}': {}", proxy, e))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(ca_cert) = &network.ca_cert {
+        let pem = std::fs::read(ca_cert).with_context(|| format!("Failed to read CA certificate {}", ca_cert))?;
+        // 一个文件中可以有多个证书 (证书链或多个 CA)
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid CA certificate {}", ca_cert))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    match (&network.client_cert, &network.client_key) {
+        (Some(cert), Some(key)) => {
+            let mut pem = std::fs::read(cert).with_context(|| format!("Failed to read client certificate {}", cert))?;
+            pem.push(b'\n');
+            pem.extend(std::fs::read(key).with_context(|| format!("Failed to read client key {}", key))?);
+            let identity = reqwest::Identity::from_pem(&pem)
+                .with_context(|| format!("Invalid client certificate or key ({}, {})", cert, key))?;
+            // PEM 格式的客户端身份只有 rustls 后端支持
+            builder = builder.use_rustls_tls().identity(identity);
+        }
+        (None, None) => {}
+        _ => anyhow::bail!("'client_cert' and 'client_key' must be set together"),
+    }
+    if network.insecure_skip_verify == Some(true) {
+        log::warn!("TLS certificate verification is disabled");
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    Ok(builder)
+}
+
+/// 工具调用可能运行很久，不受单个请求的超时限制
+fn is_tool_call(message: &ClientJsonRpcMessage) -> bool {
+    matches!(message, JsonRpcMessage::Request(request) if matches!(request.request, ClientRequest::CallToolRequest(_)))
+}
+
+/// 请求头的名称和值不合法时直接报错，避免请求在服务器端因缺少认证而失败
+fn insert_header(map: &mut HeaderMap, name: &str, value: &str) -> Result<()> {
+    let header_name = name
+        .parse::<HeaderName>()
+        .map_err(|_| anyhow::anyhow!("Invalid header name '{}'", name))?;
+    let mut header_value = HeaderValue::from_str(value)
+        .map_err(|_| anyhow::anyhow!("Invalid value for header '{}'", name))?;
+    header_value.set_sensitive(true);
+    map.insert(header_name, header_value);
+    Ok(())
+}
+
+fn is_unauthorized(e: &reqwest::Error) -> bool {
+    e.status() == Some(StatusCode::UNAUTHORIZED)
+}
+
+fn sse_unauthorized(e: &SseTransportError<reqwest::Error>) -> Option<String> {
+    match e {
+        SseTransportError::Client(e) if is_unauthorized(e) => Some(String::new()),
+        _ => None,
+    }
+}
+
+impl SseClient for AuthClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: http::Uri,
+        message: ClientJsonRpcMessage,
+        auth_token: Option<String>,
+    ) -> Result<(), SseTransportError<Self::Error>> {
+        self.with_token(
+            auth_token,
+            |token| SseClient::post_message(&self.http, uri.clone(), message.clone(), token),
+            sse_unauthorized,
+            SseTransportError::Io,
+        )
+        .await
+    }
+
+    async fn get_stream(
+        &self,
+        uri: http::Uri,
+        last_event_id: Option<String>,
+        auth_token: Option<String>,
+    ) -> Result<BoxStream<'static, Result<Sse, SseError>>, SseTransportError<Self::Error>> {
+        self.with_token(
+            auth_token,
+            |token| SseClient::get_stream(&self.streaming, uri.clone(), last_event_id.clone(), token),
+            sse_unauthorized,
+            SseTransportError::Io,
+        )
+        .await
+    }
+}
+
+fn http_unauthorized(e: &StreamableHttpError<reqwest::Error>) -> Option<String> {
+    match e {
+        StreamableHttpError::Client(e) if is_unauthorized(e) => Some(String::new()),
+        StreamableHttpError::AuthRequired(e) => Some(e.www_authenticate_header.clone()),
+        _ => None,
+    }
+}
+
+impl StreamableHttpClient for AuthClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Arc<str>,
+        message: ClientJsonRpcMessage,
+        session_id: Option<Arc<str>>,
+        auth_header: Option<String>,
+    ) -> Result<StreamableHttpPostResponse, StreamableHttpError<Self::Error>> {
+        let http = if is_tool_call(&message) { &self.streaming } else { &self.http };
+        self.with_token(
+            auth_header,
+            |token| StreamableHttpClient::post_message(http, uri.clone(), message.clone(), session_id.clone(), token),
+            http_unauthorized,
+            StreamableHttpError::Io,
+        )
+        .await
+    }
+
+    async fn delete_session(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        auth_header: Option<String>,
+    ) -> Result<(), StreamableHttpError<Self::Error>> {
+        self.with_token(
+            auth_header,
+            |token| StreamableHttpClient::delete_session(&self.http, uri.clone(), session_id.clone(), token),
+            http_unauthorized,
+            StreamableHttpError::Io,
+        )
+        .await
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        last_event_id: Option<String>,
+        auth_header: Option<String>,
+    ) -> Result<BoxStream<'static, Result<Sse, SseError>>, StreamableHttpError<Self::Error>> {
+        self.with_token(
+            auth_header,
+            |token| StreamableHttpClient::get_stream(&self.streaming, uri.clone(), session_id.clone(), last_event_id.clone(), token),
+            http_unauthorized,
+            StreamableHttpError::Io,
+        )
+        .await
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::{CancelledNotificationParam, ProgressNotificationParam, ProgressToken, ServerResult};
+use rmcp::service::RequestHandle;
+use rmcp::RoleClient;
+use tokio::sync::watch;
+
+use crate::console;
+use super::ClientError;
+
+/// 正在等待响应的请求，收到对应进度通知时在状态行上实时显示
+/// 进度令牌由各连接独立分配，因此以 (服务器, 令牌) 为键
+#[derive(Default)]
+pub struct ProgressTracker {
+    active: Mutex<HashMap<(String, ProgressToken), String>>,
+}
+
+impl ProgressTracker {
+    fn begin(&self, server: &str, token: &ProgressToken, label: String) {
+        self.active
+            .lock()
+            .unwrap()
+            .insert((server.to_string(), token.clone()), label);
+    }
+
+    fn end(&self, server: &str, token: &ProgressToken) {
+        self.active
+            .lock()
+            .unwrap()
+            .remove(&(server.to_string(), token.clone()));
+        console::global().clear_status();
+    }
+
+    /// 属于进行中请求的进度通知画到状态行上并返回 true，其余交给事件流
+    pub fn update(&self, server: &str, params: &ProgressNotificationParam) -> bool {
+        let active = self.active.lock().unwrap();
+        let Some(label) = active.get(&(server.to_string(), params.progress_token.clone())) else {
+            return false;
+        };
+        console::global().set_status(&progress_line(label, params));
+        true
+    }
+}
+
+fn progress_line(label: &str, params: &ProgressNotificationParam) -> String {
+    const WIDTH: usize = 20;
+    let amount = match params.total {
+        Some(total) if total > 0.0 => {
+            let ratio = (params.progress / total).clamp(0.0, 1.0);
+            let filled = (ratio * WIDTH as f64).round() as usize;
+            format!(
+                "[{}{}] {:>3.0}% ({}/{})",
+                "█".repeat(filled),
+                "░".repeat(WIDTH - filled),
+                ratio * 100.0,
+                params.progress,
+                total
+            )
+        }
+        _ => format!("{}", params.progress),
+    };
+    let message = params.message.as_deref().unwrap_or_default();
+    format!("{} {} {}", label.cyan(), amount, message.dimmed())
+        .trim_end()
+        .to_string()
+}
+
+/// 进程中唯一的 Ctrl+C 监听，每按一次计数加一
+static INTERRUPTS: OnceLock<watch::Sender<u64>> = OnceLock::new();
+
+/// 启动时安装 Ctrl+C 监听；之后每次按下都交给正在等待的操作取消，
+/// 没有操作在等待时 (如读取输入) 按默认行为退出进程
+pub fn listen_for_interrupts() {
+    if INTERRUPTS.set(watch::channel(0).0).is_err() {
+        return;
+    }
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            let interrupts = INTERRUPTS.get().expect("installed above");
+            if interrupts.receiver_count() == 0 {
+                std::process::exit(130);
+            }
+            interrupts.send_modify(|count| *count += 1);
+        }
+    });
+}
+
+/// 等到下一次 Ctrl+C；没有安装监听时 (如测试中) 永远不返回
+async fn interrupted() {
+    match INTERRUPTS.get() {
+        Some(interrupts) => {
+            let mut receiver = interrupts.subscribe();
+            let _ = receiver.changed().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// 按 Ctrl+C 时放弃等待 operation，返回 ClientError::Cancelled；
+/// operation 内部自己处理取消的 (如 await_cancellable) 先得到机会
+pub async fn cancellable<T>(operation: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::select! {
+        biased;
+        result = operation => result,
+        _ = interrupted() => Err(ClientError::Cancelled("by user".to_string()).into()),
+    }
+}
+
+/// 请求没有完成时的结局
+enum Abandoned {
+    Cancelled,
+    TimedOut(Duration),
+}
+
+/// 等待请求完成；期间显示进度，按 Ctrl+C 或超过 timeout 时向服务器发送
+/// `notifications/cancelled` 并放弃等待
+pub(crate) async fn await_cancellable(
+    tracker: &ProgressTracker,
+    server: &str,
+    label: String,
+    handle: RequestHandle<RoleClient>,
+    timeout: Option<Duration>,
+) -> Result<ServerResult> {
+    let RequestHandle { rx, peer, id, progress_token, .. } = handle;
+    tracker.begin(server, &progress_token, label);
+
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    let outcome = tokio::select! {
+        response = rx => Ok(response),
+        _ = interrupted() => Err(Abandoned::Cancelled),
+        _ = deadline => Err(Abandoned::TimedOut(timeout.unwrap_or_default())),
+    };
+    tracker.end(server, &progress_token);
+
+    let abandoned = match outcome {
+        Ok(Ok(response)) => return Ok(response?),
+        Ok(Err(_)) => anyhow::bail!("Connection to '{}' closed before the response arrived", server),
+        Err(abandoned) => abandoned,
+    };
+    let reason = match abandoned {
+        Abandoned::Cancelled => "Cancelled by user".to_string(),
+        Abandoned::TimedOut(timeout) => format!("Timed out after {}s", timeout.as_secs()),
+    };
+    // 有的传输要等上一个请求返回才发出下一条消息，通知在后台发送，不再等待
+    let request_id = id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = peer.notify_cancelled(CancelledNotificationParam { request_id, reason: Some(reason) }).await {
+            log::debug!("failed to send notifications/cancelled: {}", e);
+        }
+    });
+    match abandoned {
+        Abandoned::Cancelled => Err(ClientError::Cancelled(format!("request {} on '{}'", id, server)).into()),
+        Abandoned::TimedOut(timeout) => Err(ClientError::TimedOut(format!(
+            "after {}s waiting for request {} on '{}'",
+            timeout.as_secs(),
+            id,
+            server
+        ))
+        .into()),
+    }
+}
@@ -1,14 +1,66 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use rmcp::{service::RunningService, RoleClient};
+use rmcp::model::Root;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use crate::config::McpServerConfig;
+use super::events::{ClientEvent, EventSink};
+use super::handler::{ClientService, HandlerContext};
+use super::health::{HealthBoard, Reconnected};
+use super::output::{FailedServer, OutputFormat, ServerListing};
 
 pub struct ClientManager {
-    pub(crate) clients: HashMap<String, Arc<RunningService<RoleClient, ()>>>,
+    pub(crate) clients: HashMap<String, Arc<ClientService>>,
+    /// 从配置文件连接过的服务器，重试时使用
+    pub(crate) configs: BTreeMap<String, McpServerConfig>,
+    /// 连接失败的服务器及错误信息
+    pub(crate) failed: BTreeMap<String, String>,
+    pub(crate) output: OutputFormat,
+    /// 列表命令默认的每页条目数，None 表示不分页
+    pub(crate) page_size: Option<usize>,
+    pub(crate) context: Arc<HandlerContext>,
+    /// 命令行指定了采样或表单模式时，配置文件中的对应设置不再生效
+    pub(crate) sampling_pinned: bool,
+    pub(crate) elicitation_pinned: bool,
+    pub(crate) cli_roots: Vec<Root>,
+    /// 交互模式下为每个服务器运行后台健康检查和自动重连
+    pub(crate) health_checks: bool,
+    pub(crate) health: Arc<HealthBoard>,
+    pub(crate) monitors: HashMap<String, (u64, JoinHandle<()>)>,
+    pub(crate) monitor_generation: u64,
+    pub(crate) reconnected_tx: UnboundedSender<Reconnected>,
+    pub(crate) reconnected_rx: UnboundedReceiver<Reconnected>,
+    event_rx: Option<UnboundedReceiver<ClientEvent>>,
 }
 
 impl ClientManager {
     pub fn new() -> Self {
-        Self { clients: HashMap::new() }
+        let (events, event_rx) = EventSink::channel();
+        let (reconnected_tx, reconnected_rx) = mpsc::unbounded_channel();
+        Self {
+            clients: HashMap::new(),
+            configs: BTreeMap::new(),
+            failed: BTreeMap::new(),
+            output: OutputFormat::default(),
+            page_size: None,
+            context: Arc::new(HandlerContext::new(events)),
+            sampling_pinned: false,
+            elicitation_pinned: false,
+            cli_roots: Vec::new(),
+            health_checks: false,
+            health: Arc::default(),
+            monitors: HashMap::new(),
+            monitor_generation: 0,
+            reconnected_tx,
+            reconnected_rx,
+            event_rx: Some(event_rx),
+        }
+    }
+
+    /// 取走服务器通知的接收端，只能取一次
+    pub fn take_events(&mut self) -> Option<UnboundedReceiver<ClientEvent>> {
+        self.event_rx.take()
     }
 
     pub fn list_servers(&self) -> Vec<String> {
@@ -16,4 +68,32 @@ impl ClientManager {
         names.sort();
         names
     }
+
+    pub fn server_listing(&self) -> ServerListing {
+        ServerListing {
+            servers: self.list_servers(),
+            failed: self
+                .failed
+                .iter()
+                .map(|(name, error)| FailedServer {
+                    name: name.clone(),
+                    error: error.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// 关闭所有连接并等待子进程退出
+    pub async fn shutdown(&mut self) {
+        self.stop_monitors();
+        for (_, client) in self.clients.drain() {
+            if let Ok(service) = Arc::try_unwrap(client) {
+                let _ = service.cancel().await;
+            }
+        }
+        // 连接超时被丢弃的子进程由后台任务结束，退出前留出一点时间
+        if !self.failed.is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
 }
@@ -1,7 +1,37 @@
 mod manager;
+mod catalog;
 mod connection;
+mod auth;
+mod http;
+mod oauth;
+mod startup;
+mod health;
+mod error;
+mod events;
+mod handler;
+mod output;
+mod paging;
 mod tools;
 mod resources;
 mod prompts;
+mod sampling;
+mod roots;
+mod elicitation;
+mod form;
+mod completion;
+mod config;
+mod inflight;
+#[cfg(test)]
+mod oauth_flow_tests;
 
 pub use manager::ClientManager;
+pub use error::{exit_code, ClientError};
+pub use completion::{CompletionSource, CompletionTarget, NameKind};
+pub use events::ClientEvent;
+pub use inflight::{cancellable, listen_for_interrupts};
+pub use handler::ClientService;
+pub use output::OutputFormat;
+pub use paging::ListView;
+pub use tools::parse_tool_spec;
+pub use resources::parse_resource_spec;
+pub use prompts::parse_prompt_spec;
@@ -0,0 +1,655 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::{Context, Result};
+use base64::Engine;
+use colored::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::config::{NetworkConfig, OAuthConfig, DEFAULT_REQUEST_TIMEOUT};
+use crate::redact;
+use super::http::client_builder;
+
+/// 访问令牌到期前提前这么多秒刷新
+const REFRESH_MARGIN: u64 = 60;
+/// 等待用户在浏览器中完成授权的最长时间
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+const CLIENT_NAME: &str = "mcpcs-client";
+
+/// 保存在 ~/.mcpcsrs/tokens/<server>.json 中的授权结果，连同刷新令牌所需的端点和客户端信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredTokens {
+    /// 授权时的服务器地址；配置中的地址变了就不再使用
+    server_url: String,
+    resource: String,
+    token_endpoint: String,
+    client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    redirect_uri: String,
+    access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+    /// 过期时间 (Unix 秒)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+}
+
+impl StoredTokens {
+    fn expiring(&self) -> bool {
+        self.expires_at.is_some_and(|at| now() + REFRESH_MARGIN >= at)
+    }
+
+    /// 令牌和客户端密钥不能出现在诊断输出中
+    fn register_secrets(&self) {
+        redact::register(&self.access_token);
+        for secret in [&self.refresh_token, &self.client_secret].into_iter().flatten() {
+            redact::register(secret);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+/// RFC 9728 受保护资源元数据
+#[derive(Default, Deserialize)]
+struct ProtectedResource {
+    resource: Option<String>,
+    #[serde(default)]
+    authorization_servers: Vec<String>,
+    #[serde(default)]
+    scopes_supported: Vec<String>,
+}
+
+/// RFC 8414 授权服务器元数据
+#[derive(Deserialize)]
+struct AuthorizationServer {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    registration_endpoint: Option<String>,
+    code_challenge_methods_supported: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct Registration {
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+/// HTTP 服务器的 OAuth 令牌来源：读取已保存的令牌，快到期或被拒绝时用刷新令牌换新的
+pub(crate) struct OAuthTokens {
+    server: String,
+    http: reqwest::Client,
+    stored: Mutex<Option<StoredTokens>>,
+    /// 令牌缺失或无法刷新时服务器返回的 WWW-Authenticate 质询
+    challenge: std::sync::Mutex<Option<String>>,
+}
+
+impl OAuthTokens {
+    /// 刷新令牌的请求与 MCP 请求走同样的代理和 TLS 设置
+    pub fn new(server: &str, url: &str, network: &NetworkConfig, connect_timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            server: server.to_string(),
+            http: token_client(network, connect_timeout)?,
+            stored: Mutex::new(load(server, url)),
+            challenge: std::sync::Mutex::new(None),
+        })
+    }
+
+    /// 返回当前访问令牌；force 为 true (如收到 401) 或快到期时先刷新，刷新失败则视为没有令牌
+    pub async fn token(&self, force: bool) -> Result<Option<String>> {
+        let mut stored = self.stored.lock().await;
+        let Some(tokens) = stored.as_ref() else {
+            return Ok(None);
+        };
+        if !force && !tokens.expiring() {
+            return Ok(Some(tokens.access_token.clone()));
+        }
+        match refresh(&self.http, tokens).await {
+            Ok(fresh) => {
+                save(&self.server, &fresh)?;
+                let token = fresh.access_token.clone();
+                *stored = Some(fresh);
+                Ok(Some(token))
+            }
+            Err(e) => {
+                log::debug!("could not refresh the OAuth token of '{}': {:#}", self.server, e);
+                *stored = None;
+                Ok(None)
+            }
+        }
+    }
+
+    /// 记下服务器最终拒绝请求时的质询，连接失败后据此发起授权
+    pub fn rejected(&self, challenge: &str) {
+        *self.challenge.lock().unwrap() = Some(challenge.to_string());
+    }
+
+    pub fn challenge(&self) -> Option<String> {
+        self.challenge.lock().unwrap().clone()
+    }
+}
+
+/// 完整走一遍授权：发现元数据、注册客户端、在浏览器中授权、用 PKCE 换取令牌并保存
+/// 元数据、注册和令牌请求使用服务器的网络设置，私有 CA 或代理后面的服务器也能完成授权
+pub(crate) async fn authorize(
+    server: &str,
+    url: &str,
+    options: &OAuthConfig,
+    challenge: &str,
+    network: &NetworkConfig,
+    connect_timeout: Duration,
+) -> Result<()> {
+    let http = token_client(network, connect_timeout)?;
+    let server_url = Url::parse(url).with_context(|| format!("invalid server url '{}'", url))?;
+    let params = challenge_params(challenge);
+
+    let resource_metadata = discover_resource(&http, &server_url, params.get("resource_metadata")).await;
+    // 旧版规范没有受保护资源元数据，授权服务器就是 MCP 服务器所在的源
+    let issuer = match resource_metadata.authorization_servers.first() {
+        Some(issuer) => Url::parse(issuer).with_context(|| format!("invalid authorization server '{}'", issuer))?,
+        None => server_url.join("/")?,
+    };
+    let metadata = discover_authorization_server(&http, &issuer)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("no OAuth authorization server metadata found for {}", issuer))?;
+    if let Some(methods) = &metadata.code_challenge_methods_supported {
+        if !methods.iter().any(|m| m == "S256") {
+            anyhow::bail!("authorization server {} does not support PKCE with S256", issuer);
+        }
+    }
+
+    let resource = resource_metadata.resource.clone().unwrap_or_else(|| canonical(&server_url));
+    let scope = if !options.scopes.is_empty() {
+        Some(options.scopes.join(" "))
+    } else if let Some(scope) = params.get("scope") {
+        Some(scope.clone())
+    } else if !resource_metadata.scopes_supported.is_empty() {
+        Some(resource_metadata.scopes_supported.join(" "))
+    } else {
+        None
+    };
+
+    let previous = load(server, url);
+    let previous_port = previous
+        .as_ref()
+        .and_then(|p| Url::parse(&p.redirect_uri).ok())
+        .and_then(|u| u.port());
+    let listener = listen(options.redirect_port, previous_port).await?;
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", listener.local_addr()?.port());
+
+    let (client_id, client_secret) = match (&options.client_id, previous) {
+        (Some(id), _) => (id.clone(), options.client_secret.clone()),
+        (None, Some(previous)) if previous.token_endpoint == metadata.token_endpoint && previous.redirect_uri == redirect_uri => {
+            (previous.client_id, previous.client_secret)
+        }
+        (None, _) => {
+            let endpoint = metadata.registration_endpoint.as_deref().ok_or_else(|| {
+                anyhow::anyhow!("authorization server {} does not support dynamic client registration; set auth.client_id", issuer)
+            })?;
+            register(&http, endpoint, &redirect_uri, scope.as_deref()).await?
+        }
+    };
+
+    let verifier = random_string(32);
+    let code_challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let state = random_string(16);
+    let mut authorization_url = Url::parse(&metadata.authorization_endpoint)
+        .with_context(|| format!("invalid authorization endpoint '{}'", metadata.authorization_endpoint))?;
+    {
+        let mut query = authorization_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("code_challenge", &code_challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", &state)
+            .append_pair("resource", &resource);
+        if let Some(scope) = &scope {
+            query.append_pair("scope", scope);
+        }
+    }
+
+    eprintln!("{} {}", "Authorization required for".yellow(), server.cyan());
+    eprintln!("Open this URL in a browser to continue:\n  {}", authorization_url);
+    open_browser(authorization_url.as_str());
+    let code = tokio::time::timeout(AUTHORIZE_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| anyhow::anyhow!("authorization of '{}' was not completed within {}s", server, AUTHORIZE_TIMEOUT.as_secs()))??;
+
+    let mut form = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code),
+        ("redirect_uri", redirect_uri.clone()),
+        ("client_id", client_id.clone()),
+        ("code_verifier", verifier),
+        ("resource", resource.clone()),
+    ];
+    if let Some(secret) = &client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    let response = request_token(&http, &metadata.token_endpoint, &form).await?;
+    let tokens = StoredTokens {
+        server_url: url.to_string(),
+        resource,
+        token_endpoint: metadata.token_endpoint,
+        client_id,
+        client_secret,
+        redirect_uri,
+        access_token: response.access_token,
+        refresh_token: response.refresh_token,
+        expires_at: response.expires_in.map(|secs| now() + secs),
+        scope: response.scope.or(scope),
+    };
+    save(server, &tokens)?;
+    eprintln!("{} {}", "Authorized:".green(), server.cyan());
+    Ok(())
+}
+
+async fn refresh(http: &reqwest::Client, tokens: &StoredTokens) -> Result<StoredTokens> {
+    let refresh_token = tokens
+        .refresh_token
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("no refresh token"))?;
+    let mut form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh_token.clone()),
+        ("client_id", tokens.client_id.clone()),
+        ("resource", tokens.resource.clone()),
+    ];
+    if let Some(secret) = &tokens.client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    let response = request_token(http, &tokens.token_endpoint, &form).await?;
+    Ok(StoredTokens {
+        access_token: response.access_token,
+        refresh_token: response.refresh_token.or(Some(refresh_token)),
+        expires_at: response.expires_in.map(|secs| now() + secs),
+        scope: response.scope.or_else(|| tokens.scope.clone()),
+        ..tokens.clone()
+    })
+}
+
+async fn request_token(http: &reqwest::Client, endpoint: &str, form: &[(&str, String)]) -> Result<TokenResponse> {
+    let response = http
+        .post(endpoint)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .form(form)
+        .send()
+        .await
+        .with_context(|| format!("token request to {} failed", endpoint))?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!("token endpoint {} returned {}: {}", endpoint, status, oauth_error(&body));
+    }
+    serde_json::from_str(&body).with_context(|| format!("unexpected token response from {}", endpoint))
+}
+
+/// RFC 7591 动态注册一个公共客户端
+async fn register(http: &reqwest::Client, endpoint: &str, redirect_uri: &str, scope: Option<&str>) -> Result<(String, Option<String>)> {
+    let mut request = serde_json::json!({
+        "client_name": CLIENT_NAME,
+        "redirect_uris": [redirect_uri],
+        "grant_types": ["authorization_code", "refresh_token"],
+        "response_types": ["code"],
+        "token_endpoint_auth_method": "none",
+    });
+    if let Some(scope) = scope {
+        request["scope"] = scope.into();
+    }
+    let response = http
+        .post(endpoint)
+        .json(&request)
+        .send()
+        .await
+        .with_context(|| format!("client registration at {} failed", endpoint))?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!("client registration at {} returned {}: {}", endpoint, status, oauth_error(&body));
+    }
+    let registration: Registration =
+        serde_json::from_str(&body).with_context(|| format!("unexpected registration response from {}", endpoint))?;
+    Ok((registration.client_id, registration.client_secret.filter(|s| !s.is_empty())))
+}
+
+/// 先用质询中的 resource_metadata，再按 RFC 9728 的 well-known 地址查找；都没有时返回空元数据
+async fn discover_resource(http: &reqwest::Client, server_url: &Url, hint: Option<&String>) -> ProtectedResource {
+    let mut candidates = Vec::new();
+    if let Some(hint) = hint.and_then(|h| server_url.join(h).ok()) {
+        candidates.push(hint);
+    }
+    let path = server_url.path().trim_end_matches('/');
+    if !path.is_empty() {
+        candidates.extend(server_url.join(&format!("/.well-known/oauth-protected-resource{}", path)).ok());
+    }
+    candidates.extend(server_url.join("/.well-known/oauth-protected-resource").ok());
+    for candidate in candidates {
+        if let Some(metadata) = fetch_json(http, &candidate).await {
+            return metadata;
+        }
+    }
+    ProtectedResource::default()
+}
+
+/// 依次尝试 RFC 8414 和 OpenID Connect 的元数据地址
+async fn discover_authorization_server(http: &reqwest::Client, issuer: &Url) -> Option<AuthorizationServer> {
+    let path = issuer.path().trim_end_matches('/');
+    let candidates = if path.is_empty() {
+        vec![
+            "/.well-known/oauth-authorization-server".to_string(),
+            "/.well-known/openid-configuration".to_string(),
+        ]
+    } else {
+        vec![
+            format!("/.well-known/oauth-authorization-server{}", path),
+            format!("/.well-known/openid-configuration{}", path),
+            format!("{}/.well-known/openid-configuration", path),
+        ]
+    };
+    for candidate in candidates {
+        let Ok(url) = issuer.join(&candidate) else { continue };
+        if let Some(metadata) = fetch_json(http, &url).await {
+            return Some(metadata);
+        }
+    }
+    None
+}
+
+async fn fetch_json<T: DeserializeOwned>(http: &reqwest::Client, url: &Url) -> Option<T> {
+    let response = match http.get(url.clone()).header(reqwest::header::ACCEPT, "application/json").send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            log::debug!("{} returned {}", url, response.status());
+            return None;
+        }
+        Err(e) => {
+            log::debug!("fetching {} failed: {}", url, e);
+            return None;
+        }
+    };
+    match response.json().await {
+        Ok(metadata) => Some(metadata),
+        Err(e) => {
+            log::debug!("unexpected metadata at {}: {}", url, e);
+            None
+        }
+    }
+}
+
+/// 解析 `Bearer resource_metadata="...", scope="..."` 中的参数
+fn challenge_params(challenge: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = challenge.trim();
+    if let Some((scheme, after)) = rest.split_once(' ') {
+        if !scheme.contains('=') {
+            rest = after;
+        }
+    }
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_ascii_lowercase();
+        let after = after.trim_start();
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let mut value = String::new();
+                let mut chars = quoted.char_indices();
+                let mut end = quoted.len();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                        '"' => {
+                            end = i + 1;
+                            break;
+                        }
+                        c => value.push(c),
+                    }
+                }
+                (value, &quoted[end..])
+            }
+            None => {
+                let end = after.find(',').unwrap_or(after.len());
+                (after[..end].trim().to_string(), &after[end..])
+            }
+        };
+        params.insert(key, value);
+        rest = remaining.trim_start().trim_start_matches(',');
+    }
+    params
+}
+
+/// 优先使用配置的端口，其次沿用上次注册时的端口 (这样可以复用已注册的客户端)，否则随机
+async fn listen(port: Option<u16>, previous: Option<u16>) -> Result<TcpListener> {
+    if let Some(port) = port {
+        return TcpListener::bind(("127.0.0.1", port))
+            .await
+            .with_context(|| format!("cannot listen on 127.0.0.1:{} for the OAuth redirect", port));
+    }
+    if let Some(port) = previous {
+        if let Ok(listener) = TcpListener::bind(("127.0.0.1", port)).await {
+            return Ok(listener);
+        }
+    }
+    TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .context("cannot listen on 127.0.0.1 for the OAuth redirect")
+}
+
+/// 等待浏览器带着授权码回到 /callback；state 不符的请求忽略
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buffer = vec![0; 8192];
+        let n = stream.read(&mut buffer).await.unwrap_or(0);
+        let request = String::from_utf8_lossy(&buffer[..n]);
+        let target = request.split_whitespace().nth(1).unwrap_or("/");
+        let Ok(url) = Url::parse(&format!("http://127.0.0.1{}", target)) else {
+            respond(&mut stream, "400 Bad Request", "Bad request.").await;
+            continue;
+        };
+        if url.path() != "/callback" {
+            respond(&mut stream, "404 Not Found", "Not found.").await;
+            continue;
+        }
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        if params.get("state").map(String::as_str) != Some(state) {
+            respond(&mut stream, "400 Bad Request", "Unexpected authorization response.").await;
+            continue;
+        }
+        if let Some(error) = params.get("error") {
+            respond(&mut stream, "200 OK", "Authorization failed. You can close this window.").await;
+            let description = params.get("error_description").map(|d| format!(": {}", d)).unwrap_or_default();
+            anyhow::bail!("authorization was denied ({}{})", error, description);
+        }
+        if let Some(code) = params.get("code") {
+            respond(&mut stream, "200 OK", "Authorization complete. You can close this window.").await;
+            return Ok(code.clone());
+        }
+        respond(&mut stream, "400 Bad Request", "Missing authorization code.").await;
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!("<!doctype html><html><body><p>{}</p></body></html>", message);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// 测试中代替浏览器访问授权地址
+#[cfg(test)]
+pub(crate) static TEST_BROWSER: std::sync::OnceLock<fn(&str)> = std::sync::OnceLock::new();
+
+/// 尽量自动打开浏览器，BROWSER 环境变量优先；打不开时用户仍可手动访问打印出的地址
+fn open_browser(url: &str) {
+    #[cfg(test)]
+    if let Some(browser) = TEST_BROWSER.get() {
+        return browser(url);
+    }
+    let command = std::env::var("BROWSER").ok().filter(|b| !b.is_empty()).unwrap_or_else(|| {
+        if cfg!(target_os = "macos") {
+            "open".to_string()
+        } else if cfg!(windows) {
+            "explorer".to_string()
+        } else {
+            "xdg-open".to_string()
+        }
+    });
+    let spawned = tokio::process::Command::new(&command)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Err(e) = spawned {
+        log::debug!("could not open a browser with '{}': {}", command, e);
+    }
+}
+
+/// 错误响应中的 error 和 error_description，不是 JSON 时原样返回
+fn oauth_error(body: &str) -> String {
+    #[derive(Deserialize)]
+    struct OAuthError {
+        error: String,
+        error_description: Option<String>,
+    }
+    match serde_json::from_str::<OAuthError>(body) {
+        Ok(e) => match e.error_description {
+            Some(description) => format!("{} ({})", e.error, description),
+            None => e.error,
+        },
+        Err(_) => body.trim().to_string(),
+    }
+}
+
+/// RFC 8707 资源指示符：去掉片段的服务器地址
+fn canonical(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
+
+/// 授权相关请求不带服务器的自定义请求头，也不跟随重定向
+fn token_client(network: &NetworkConfig, connect_timeout: Duration) -> Result<reqwest::Client> {
+    let timeout = network.timeout.map(Duration::from_secs).unwrap_or(DEFAULT_REQUEST_TIMEOUT);
+    client_builder(Default::default(), network, connect_timeout)?
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build HTTP client: {}", e))
+}
+
+fn random_string(bytes: usize) -> String {
+    let data: Vec<u8> = (0..bytes).map(|_| rand::random::<u8>()).collect();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+fn tokens_path(server: &str) -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    let file: String = server
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    Ok(home.join(".mcpcsrs").join("tokens").join(format!("{}.json", file)))
+}
+
+/// 读取服务器保存的令牌；文件不存在、无法解析或属于另一个地址时返回 None
+fn load(server: &str, url: &str) -> Option<StoredTokens> {
+    let path = tokens_path(server).ok()?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<StoredTokens>(&content) {
+        Ok(tokens) if tokens.server_url == url => {
+            tokens.register_secrets();
+            Some(tokens)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("ignoring {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// 令牌文件只允许当前用户读写
+fn save(server: &str, tokens: &StoredTokens) -> Result<()> {
+    tokens.register_secrets();
+    let path = tokens_path(server)?;
+    let dir = path.parent().expect("token path has a parent");
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let content = serde_json::to_string_pretty(tokens)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    let mut file = options.open(&path).with_context(|| format!("Failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::io::Write::write_all(&mut file, content.as_bytes()).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_params_parse_quoted_and_bare_values() {
+        let params = challenge_params(
+            r#"Bearer resource_metadata="https://example.com/.well-known/oauth-protected-resource", scope="read write", error=invalid_token"#,
+        );
+        assert_eq!(params["resource_metadata"], "https://example.com/.well-known/oauth-protected-resource");
+        assert_eq!(params["scope"], "read write");
+        assert_eq!(params["error"], "invalid_token");
+    }
+
+    #[test]
+    fn challenge_params_handle_escapes_commas_and_case() {
+        let params = challenge_params(r#"Bearer Realm="a \"quoted\", value",Scope=tools"#);
+        assert_eq!(params["realm"], r#"a "quoted", value"#);
+        assert_eq!(params["scope"], "tools");
+    }
+
+    #[test]
+    fn challenge_params_without_scheme_or_parameters() {
+        assert!(challenge_params("").is_empty());
+        assert!(challenge_params("Bearer").is_empty());
+        assert_eq!(challenge_params(r#"scope="a b""#)["scope"], "a b");
+    }
+
+    #[test]
+    fn challenge_params_tolerate_unterminated_quotes() {
+        let params = challenge_params(r#"Bearer scope="read"#);
+        assert_eq!(params["scope"], "read");
+    }
+}
@@ -0,0 +1,278 @@
+//! 用进程内的存根服务器走一遍 OAuth 授权：401 质询、元数据发现、动态注册、
+//! 浏览器回调、PKCE 换取令牌、保存令牌，以及访问令牌失效后的刷新
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use base64::Engine;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use url::Url;
+
+use crate::config::McpServerConfig;
+use super::oauth::TEST_BROWSER;
+use super::startup::{establish, ConnectStatus};
+use super::ClientManager;
+
+#[derive(Default)]
+struct StubState {
+    redirect_uris: Vec<String>,
+    /// 授权码 -> code_challenge
+    codes: HashMap<String, String>,
+    access_tokens: HashSet<String>,
+    refresh_tokens: HashSet<String>,
+    issued: usize,
+    verified: usize,
+    refreshed: usize,
+}
+
+struct Request {
+    method: String,
+    url: Url,
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+struct Response {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl Response {
+    fn json(status: &'static str, body: Value) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    fn empty(status: &'static str) -> Self {
+        Self { status, headers: Vec::new(), body: String::new() }
+    }
+}
+
+/// 同时扮演 MCP 服务器 (受保护资源) 和授权服务器
+async fn start_stub() -> (String, Arc<Mutex<StubState>>) {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+    let base = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let state = Arc::new(Mutex::new(StubState::default()));
+    let (server_base, server_state) = (base.clone(), state.clone());
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, server_base.clone(), server_state.clone()));
+        }
+    });
+    (base, state)
+}
+
+async fn serve(stream: TcpStream, base: String, state: Arc<Mutex<StubState>>) {
+    let mut reader = BufReader::new(stream);
+    let Some(request) = read_request(&mut reader, &base).await else {
+        return;
+    };
+    let response = route(&request, &base, &mut state.lock().unwrap());
+    let mut head = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n", response.status, response.body.len());
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    let stream = reader.get_mut();
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(response.body.as_bytes()).await;
+}
+
+async fn read_request(reader: &mut BufReader<TcpStream>, base: &str) -> Option<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let url = Url::parse(&format!("{}{}", base, parts.next()?)).ok()?;
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+    let length = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.ok()?;
+    Some(Request { method, url, headers, body: String::from_utf8(body).ok()? })
+}
+
+fn route(request: &Request, base: &str, state: &mut StubState) -> Response {
+    let query: HashMap<String, String> = request.url.query_pairs().into_owned().collect();
+    let form: HashMap<String, String> = url::form_urlencoded::parse(request.body.as_bytes()).into_owned().collect();
+    match (request.method.as_str(), request.url.path()) {
+        ("GET", "/.well-known/oauth-protected-resource/mcp") => Response::json(
+            "200 OK",
+            json!({ "resource": format!("{}/mcp", base), "authorization_servers": [base], "scopes_supported": ["tools"] }),
+        ),
+        ("GET", "/.well-known/oauth-authorization-server") => Response::json(
+            "200 OK",
+            json!({
+                "issuer": base,
+                "authorization_endpoint": format!("{}/authorize", base),
+                "token_endpoint": format!("{}/token", base),
+                "registration_endpoint": format!("{}/register", base),
+                "code_challenge_methods_supported": ["S256"],
+            }),
+        ),
+        ("POST", "/register") => {
+            let registration: Value = serde_json::from_str(&request.body).unwrap();
+            assert_eq!(registration["token_endpoint_auth_method"], "none");
+            for uri in registration["redirect_uris"].as_array().unwrap() {
+                state.redirect_uris.push(uri.as_str().unwrap().to_string());
+            }
+            Response::json("201 Created", json!({ "client_id": "stub-client" }))
+        }
+        ("GET", "/authorize") => {
+            assert_eq!(query["client_id"], "stub-client");
+            assert_eq!(query["code_challenge_method"], "S256");
+            assert_eq!(query["resource"], format!("{}/mcp", base));
+            assert_eq!(query["scope"], "tools");
+            let redirect_uri = &query["redirect_uri"];
+            assert!(state.redirect_uris.contains(redirect_uri), "unregistered redirect uri {}", redirect_uri);
+            let code = format!("code-{}", state.codes.len() + 1);
+            state.codes.insert(code.clone(), query["code_challenge"].clone());
+            let mut location = Url::parse(redirect_uri).unwrap();
+            location.query_pairs_mut().append_pair("code", &code).append_pair("state", &query["state"]);
+            Response {
+                status: "302 Found",
+                headers: vec![("Location", location.to_string())],
+                body: String::new(),
+            }
+        }
+        ("POST", "/token") => match form["grant_type"].as_str() {
+            "authorization_code" => {
+                let Some(challenge) = state.codes.remove(&form["code"]) else {
+                    return Response::json("400 Bad Request", json!({ "error": "invalid_grant" }));
+                };
+                let computed = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(form["code_verifier"].as_bytes()));
+                if computed != challenge {
+                    return Response::json("400 Bad Request", json!({ "error": "invalid_grant", "error_description": "PKCE verification failed" }));
+                }
+                state.verified += 1;
+                issue(state)
+            }
+            "refresh_token" => {
+                if !state.refresh_tokens.remove(&form["refresh_token"]) {
+                    return Response::json("400 Bad Request", json!({ "error": "invalid_grant" }));
+                }
+                state.refreshed += 1;
+                issue(state)
+            }
+            other => Response::json("400 Bad Request", json!({ "error": "unsupported_grant_type", "error_description": other })),
+        },
+        ("POST", "/mcp") => {
+            let token = request.headers.get("authorization").and_then(|a| a.strip_prefix("Bearer "));
+            if !token.is_some_and(|t| state.access_tokens.contains(t)) {
+                let challenge = format!(r#"Bearer resource_metadata="{}/.well-known/oauth-protected-resource/mcp""#, base);
+                return Response {
+                    status: "401 Unauthorized",
+                    headers: vec![("WWW-Authenticate", challenge)],
+                    body: String::new(),
+                };
+            }
+            let message: Value = serde_json::from_str(&request.body).unwrap();
+            let Some(id) = message.get("id") else {
+                return Response::empty("202 Accepted");
+            };
+            let result = match message["method"].as_str() {
+                Some("initialize") => json!({
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "serverInfo": { "name": "stub", "version": "1" },
+                }),
+                _ => json!({}),
+            };
+            Response::json("200 OK", json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+        }
+        ("GET", "/mcp") => Response::empty("405 Method Not Allowed"),
+        ("DELETE", "/mcp") => Response::empty("200 OK"),
+        _ => Response::empty("404 Not Found"),
+    }
+}
+
+fn issue(state: &mut StubState) -> Response {
+    state.issued += 1;
+    let access = format!("access-{}", state.issued);
+    let refresh = format!("refresh-{}", state.issued);
+    state.access_tokens.insert(access.clone());
+    state.refresh_tokens.insert(refresh.clone());
+    Response::json(
+        "200 OK",
+        json!({ "access_token": access, "refresh_token": refresh, "token_type": "Bearer", "expires_in": 3600 }),
+    )
+}
+
+/// 跟随授权服务器的重定向回到本地回调地址，就像用户在浏览器中点了同意
+fn visit(url: &str) {
+    let url = url.to_string();
+    tokio::spawn(async move {
+        let _ = reqwest::get(url).await;
+    });
+}
+
+fn saved_tokens(home: &std::path::Path) -> (Value, u32) {
+    let path = home.join(".mcpcsrs").join("tokens").join("stub.json");
+    let content = std::fs::read_to_string(&path).unwrap();
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(&path).unwrap().permissions().mode() & 0o777
+    };
+    #[cfg(not(unix))]
+    let mode = 0o600;
+    (serde_json::from_str(&content).unwrap(), mode)
+}
+
+#[tokio::test]
+async fn authorizes_saves_and_refreshes_tokens() {
+    let home: PathBuf = std::env::temp_dir().join(format!("mcpcs-oauth-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+    std::env::set_var("HOME", &home);
+    TEST_BROWSER.set(visit).unwrap();
+
+    let (base, state) = start_stub().await;
+    let name = "stub".to_string();
+    let config: McpServerConfig =
+        serde_json::from_value(json!({ "transport": "http", "url": format!("{}/mcp", base) })).unwrap();
+
+    // 没有令牌：收到 401 质询后完成授权并重新连接
+    let mut manager = ClientManager::new();
+    let summary = manager.connect_all([(&name, &config)]).await;
+    let report = &summary.servers[0];
+    assert_eq!(report.status, ConnectStatus::Connected, "{:?}", report.error);
+    {
+        let state = state.lock().unwrap();
+        assert_eq!(state.redirect_uris.len(), 1);
+        assert_eq!(state.verified, 1);
+    }
+    let (tokens, mode) = saved_tokens(&home);
+    assert_eq!(tokens["access_token"], "access-1");
+    assert_eq!(tokens["refresh_token"], "refresh-1");
+    assert_eq!(tokens["client_id"], "stub-client");
+    assert_eq!(tokens["resource"], format!("{}/mcp", base));
+    assert_eq!(mode, 0o600);
+
+    // 访问令牌被吊销：新连接用刷新令牌换一个新的，不再要求授权
+    state.lock().unwrap().access_tokens.remove("access-1");
+    manager.close(&name).await;
+    let client = establish(&name, &config, &manager.context).await.unwrap();
+    assert_eq!(state.lock().unwrap().refreshed, 1);
+    let (tokens, _) = saved_tokens(&home);
+    assert_eq!(tokens["access_token"], "access-2");
+    assert_eq!(tokens["refresh_token"], "refresh-2");
+    let _ = client.cancel().await;
+
+    let _ = std::fs::remove_dir_all(&home);
+}
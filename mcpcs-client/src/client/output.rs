@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::redact::redact;
+
+use super::ClientManager;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+        }
+    }
+}
+
+/// 操作结果文档：JSON 模式下原样序列化，文本模式下彩色渲染
+pub trait Render: Serialize {
+    fn render_text(&self) -> Result<()>;
+}
+
+impl ClientManager {
+    pub fn output(&self) -> OutputFormat {
+        self.output
+    }
+
+    pub fn set_output(&mut self, output: OutputFormat) {
+        self.output = output;
+    }
+
+    /// 按当前输出格式打印结果
+    pub fn emit<T: Render>(&self, doc: &T) -> Result<()> {
+        match self.output {
+            OutputFormat::Text => doc.render_text(),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(doc)?);
+                Ok(())
+            }
+        }
+    }
+
+    /// 过程提示 (如 "Calling ... on ...")，JSON 模式下不输出以保证 stdout 只有结果文档
+    pub(crate) fn status(&self, message: impl Display) {
+        if self.output == OutputFormat::Text {
+            println!("{}", message);
+        }
+    }
+
+    /// 按当前输出格式报告错误，JSON 模式下输出 `{"error": ..., "exit_code": ...}`
+    pub fn report_error(&self, context: &str, err: &anyhow::Error) {
+        match self.output {
+            OutputFormat::Text => eprintln!("{} {}", context.red(), redact(&format!("{:#}", err))),
+            OutputFormat::Json => {
+                let doc = serde_json::json!({
+                    "error": redact(&format!("{:#}", err)),
+                    "exit_code": super::exit_code(err),
+                });
+                println!("{}", doc);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerListing {
+    pub servers: Vec<String>,
+    /// 连接失败、可以重试的服务器
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<FailedServer>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedServer {
+    pub name: String,
+    pub error: String,
+}
+
+impl Render for ServerListing {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
+            println!("{}", "No connected servers.".yellow());
+        } else {
+            for s in &self.servers {
+                println!("{} {}", "-".dimmed(), s.cyan());
+            }
+        }
+        for f in &self.failed {
+            println!("{} {} {} {}", "-".dimmed(), f.name.cyan(), "[FAILED]".red().bold(), f.error.dimmed());
+        }
+        Ok(())
+    }
+}
+
+/// 从 (服务器, 名称) 对中找出在多个服务器上重复出现的名称
+pub(crate) fn find_conflicts<'a>(
+    entries: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut name_to_servers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (server_name, name) in entries {
+        name_to_servers
+            .entry(name.to_string())
+            .or_default()
+            .push(server_name.to_string());
+    }
+    name_to_servers.retain(|_, servers| servers.len() > 1);
+    name_to_servers
+}
@@ -0,0 +1,174 @@
+use std::io::{self, IsTerminal};
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::console;
+use super::output::{OutputFormat, Render};
+use super::{ClientError, ClientManager};
+
+/// 列表的显示范围：limit 为最多显示的条目数，page_size 为交互分页时每页的条目数
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListView {
+    pub limit: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+impl ListView {
+    /// 解析 REPL 中 /list 之后的 `--limit N` 和 `--page-size N`
+    pub fn parse(args: &[&str]) -> Result<Self> {
+        let mut view = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let slot = match *arg {
+                "--limit" => &mut view.limit,
+                "--page-size" => &mut view.page_size,
+                other => return Err(ClientError::Usage(format!("Unknown option '{}'", other)).into()),
+            };
+            let value = args
+                .next()
+                .ok_or_else(|| ClientError::Usage(format!("{} requires a number", arg)))?;
+            let n = value
+                .parse::<usize>()
+                .map_err(|_| ClientError::Usage(format!("{} expects a number, got '{}'", arg, value)))?;
+            *slot = Some(n);
+        }
+        Ok(view)
+    }
+}
+
+/// 按服务器分组、可以截断和分页显示的列表
+pub trait Paged: Render + Sized {
+    /// 条目的复数名称，用于 "... 3 more tools not shown"
+    const ITEMS: &'static str;
+
+    /// 所有服务器的条目总数
+    fn item_count(&self) -> usize;
+
+    /// 只保留从第 start 个开始的 count 个条目
+    fn slice(&self, start: usize, count: usize) -> Self;
+}
+
+/// 输出的列表文档；JSON 模式下附上条目总数和是否被 --limit 截断
+#[derive(Serialize)]
+struct ListDocument<'a, T> {
+    #[serde(flatten)]
+    list: &'a T,
+    total: usize,
+    truncated: bool,
+}
+
+impl<T: Paged> Render for ListDocument<'_, T> {
+    fn render_text(&self) -> Result<()> {
+        self.list.render_text()
+    }
+}
+
+/// 从各服务器的条目中取出全局第 start 个起的 count 个；没有条目的服务器 (包括获取出错的) 只出现在第一页
+pub(crate) fn slice_groups<G, T: Clone>(
+    groups: &[G],
+    items: impl Fn(&G) -> &[T],
+    rebuild: impl Fn(&G, Vec<T>) -> G,
+    start: usize,
+    count: usize,
+) -> Vec<G> {
+    let end = start.saturating_add(count);
+    let mut offset = 0;
+    let mut sliced = Vec::new();
+    for group in groups {
+        let all = items(group);
+        let lo = start.clamp(offset, offset + all.len()) - offset;
+        let hi = end.clamp(offset, offset + all.len()) - offset;
+        if lo < hi || (all.is_empty() && start == 0) {
+            sliced.push(rebuild(group, all[lo..hi].to_vec()));
+        }
+        offset += all.len();
+    }
+    sliced
+}
+
+impl ClientManager {
+    /// 全局 --page-size：列表命令 (REPL 和一次性的 list) 未另行指定时的每页条目数，0 表示不分页
+    pub fn set_page_size(&mut self, page_size: Option<usize>) {
+        self.page_size = page_size;
+    }
+
+    /// 按 limit 截断后输出列表；文本模式下在终端中超过一页时逐页显示并等待翻页
+    pub async fn emit_list<T: Paged>(&self, doc: T, view: ListView) -> Result<()> {
+        let total = doc.item_count();
+        let doc = match view.limit {
+            Some(limit) if limit < total => doc.slice(0, limit),
+            _ => doc,
+        };
+        let shown = doc.item_count();
+
+        let page_size = view.page_size.or(self.page_size).filter(|n| *n > 0);
+        match page_size {
+            Some(size) if shown > size && self.output == OutputFormat::Text && can_page() => {
+                page(&doc, shown, size).await?;
+            }
+            _ => self.emit(&ListDocument { list: &doc, total, truncated: shown < total })?,
+        }
+
+        if shown < total {
+            self.status(format!("... {} more {} not shown (--limit {})", total - shown, T::ITEMS, shown).dimmed());
+        }
+        Ok(())
+    }
+}
+
+/// 只有标准输入输出都是终端时才分页，脚本通过管道驱动 REPL 时不会把后续命令当作翻页输入
+fn can_page() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+async fn page<T: Paged>(doc: &T, shown: usize, size: usize) -> Result<()> {
+    let mut start = 0;
+    loop {
+        doc.slice(start, size).render_text()?;
+        start += size;
+        if start >= shown {
+            return Ok(());
+        }
+        let prompt = format!("-- more ({}/{}) Enter: next page, a: all, q: stop -- ", start, shown);
+        match console::global().read_line(&prompt.dimmed().to_string()).await.as_deref().map(str::trim) {
+            Some("") => continue,
+            Some("a") => return doc.slice(start, shown).render_text(),
+            _ => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups() -> Vec<(&'static str, Vec<u32>)> {
+        vec![("a", vec![1, 2, 3]), ("empty", vec![]), ("b", vec![4, 5])]
+    }
+
+    fn slice(start: usize, count: usize) -> Vec<(&'static str, Vec<u32>)> {
+        slice_groups(&groups(), |g| &g.1, |g, items| (g.0, items), start, count)
+    }
+
+    #[test]
+    fn first_page_keeps_empty_groups() {
+        assert_eq!(slice(0, 2), vec![("a", vec![1, 2]), ("empty", vec![])]);
+    }
+
+    #[test]
+    fn later_pages_span_groups() {
+        assert_eq!(slice(2, 2), vec![("a", vec![3]), ("b", vec![4])]);
+        assert_eq!(slice(4, 10), vec![("b", vec![5])]);
+        assert!(slice(5, 10).is_empty());
+    }
+
+    #[test]
+    fn parse_view_options() {
+        let view = ListView::parse(&["--limit", "5", "--page-size", "2"]).unwrap();
+        assert_eq!((view.limit, view.page_size), (Some(5), Some(2)));
+        assert!(ListView::parse(&["--limit"]).is_err());
+        assert!(ListView::parse(&["--limit", "x"]).is_err());
+        assert!(ListView::parse(&["--bogus", "1"]).is_err());
+    }
+}
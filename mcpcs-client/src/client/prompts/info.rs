@@ -1,56 +1,52 @@
 use anyhow::Result;
 use colored::Colorize;
 use rmcp::model::Prompt;
+use serde::Serialize;
 
-use crate::client::ClientManager;
+use crate::client::output::Render;
+use crate::client::{ClientError, ClientManager};
 use super::parse_prompt_spec;
 
+#[derive(Debug, Serialize)]
+pub struct PromptDetails {
+    pub server: String,
+    pub prompt: Prompt,
+}
+
 impl ClientManager {
-    pub async fn prompt_info(&self, prompt_spec: &str) -> Result<()> {
+    pub fn prompt_info(&self, prompt_spec: &str) -> Result<()> {
+        let details = self.prompt_details(prompt_spec)?;
+        self.emit(&details)
+    }
+
+    pub fn prompt_details(&self, prompt_spec: &str) -> Result<PromptDetails> {
         let (server_name, prompt_name) = parse_prompt_spec(prompt_spec);
-        
-        if let Some(server_name) = server_name {
+
+        let server_name = match server_name {
             // 指定服务器
-            if let Some(client) = self.clients.get(server_name) {
-                if let Ok(response) = client.list_prompts(Default::default()).await {
-                    if let Some(prompt) = response.prompts.iter().find(|p| p.name == prompt_name) {
-                        print_prompt_info(server_name, prompt)?;
-                    } else {
-                        eprintln!("{} {} {} {}", "Prompt".red(), prompt_name, "not found on server".red(), server_name);
-                    }
-                }
-                return Ok(());
-            } else {
-                eprintln!("{} {}", "Server not found:".red(), server_name);
-                return Ok(());
+            Some(server_name) if self.clients.contains_key(server_name) => server_name.to_string(),
+            Some(server_name) => {
+                return Err(ClientError::ServerNotFound(server_name.to_string()).into());
             }
-        }
+            // 搜索所有服务器
+            None => self.find_prompt_server(prompt_name)?,
+        };
 
-        // 搜索所有服务器
-        let mut found = false;
-        for (server_name, client) in &self.clients {
-            if let Ok(response) = client.list_prompts(Default::default()).await {
-                if let Some(prompt) = response.prompts.iter().find(|p| p.name == prompt_name) {
-                    if found {
-                        eprintln!(
-                            "{} '{}' {}",
-                            "Prompt".yellow(),
-                            prompt_name,
-                            "found in multiple servers. Use server_name/prompt_name to specify.".yellow()
-                        );
-                        return Ok(());
-                    }
-                    print_prompt_info(server_name, prompt)?;
-                    found = true;
-                }
+        let listed = self.context.catalog.get(&server_name).prompts;
+        match listed.items.into_iter().find(|p| p.name == prompt_name) {
+            Some(prompt) => Ok(PromptDetails { server: server_name, prompt }),
+            None => Err(ClientError::NotFound {
+                kind: "Prompt",
+                name: format!("{}/{}", server_name, prompt_name),
             }
+            .into()),
         }
+    }
+}
 
-        if !found {
-            eprintln!("{} {}", "Prompt not found:".red(), prompt_name);
-        }
-
-        Ok(())
+impl Render for PromptDetails {
+    fn render_text(&self) -> Result<()> {
+        print_prompt_info(&self.server, &self.prompt)
     }
 }
 
@@ -78,10 +74,10 @@ fn print_prompt_info(server_name: &str, prompt: &Prompt) -> Result<()> {
             };
             
             println!(
-                "  {} {} {}",
+                "  {} {} [{}]",
                 "•".blue(),
                 arg.name.yellow(),
-                format!("[{}]", required_str)
+                required_str
             );
             
                 if let Some(description) = &arg.description {
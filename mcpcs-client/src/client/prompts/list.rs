@@ -1,79 +1,113 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use anyhow::Result;
 use colored::Colorize;
+use rmcp::model::PromptArgument;
+use serde::Serialize;
 
+use crate::client::output::{find_conflicts, Render};
+use crate::client::paging::{slice_groups, ListView, Paged};
 use crate::client::ClientManager;
 
+#[derive(Debug, Serialize)]
+pub struct PromptListing {
+    pub servers: Vec<ServerPrompts>,
+    /// 提示词名 -> 提供该提示词的服务器 (仅包含冲突的提示词)
+    pub conflicts: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerPrompts {
+    pub server: String,
+    pub prompts: Vec<PromptSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptSummary {
+    pub name: String,
+    pub description: String,
+    pub arguments: Vec<PromptArgument>,
+}
+
 impl ClientManager {
-    pub async fn list_prompts(&self) -> Result<()> {
-        if self.clients.is_empty() {
-            println!("{}", "No connected servers.".yellow());
-            return Ok(());
+    pub async fn list_prompts(&self, view: ListView) -> Result<()> {
+        let listing = self.prompt_listing();
+        self.emit_list(listing, view).await
+    }
+
+    pub fn prompt_listing(&self) -> PromptListing {
+        let mut servers = Vec::new();
+
+        for server_name in self.list_servers() {
+            let listed = self.context.catalog.get(&server_name).prompts;
+            let prompts = listed
+                .items
+                .into_iter()
+                .map(|p| PromptSummary {
+                    name: p.name,
+                    description: p.description.unwrap_or_default(),
+                    arguments: p.arguments.unwrap_or_default(),
+                })
+                .collect();
+            servers.push(ServerPrompts { server: server_name, prompts, error: listed.error });
         }
 
-        let mut prompt_to_servers: HashMap<String, Vec<String>> = HashMap::new();
-        let mut server_prompts: Vec<(String, Vec<(String, String, Vec<String>)>)> = Vec::new();
+        let conflicts = find_conflicts(
+            servers
+                .iter()
+                .flat_map(|s| s.prompts.iter().map(move |p| (s.server.as_str(), p.name.as_str()))),
+        );
+        PromptListing { servers, conflicts }
+    }
+}
 
-        for (server_name, client) in &self.clients {
-            let result = client.list_prompts(Default::default()).await;
-            match result {
-                Ok(response) => {
-                    let prompts: Vec<(String, String, Vec<String>)> = response
-                        .prompts
-                        .iter()
-                        .map(|p| {
-                            let args: Vec<String> = p.arguments
-                                .as_ref()
-                                .unwrap_or(&vec![])
-                                .iter()
-                                .map(|arg| {
-                                    let required = if arg.required.unwrap_or(false) {
-                                        format!("{}*", arg.name)
-                                    } else {
-                                        arg.name.clone()
-                                    };
-                                    required
-                                })
-                                .collect();
-                            (
-                                p.name.clone(),
-                                p.description.clone().unwrap_or_default(),
-                                args,
-                            )
-                        })
-                        .collect();
+impl Paged for PromptListing {
+    const ITEMS: &'static str = "prompts";
 
-                    for (prompt_name, _, _) in &prompts {
-                        prompt_to_servers
-                            .entry(prompt_name.clone())
-                            .or_default()
-                            .push(server_name.clone());
-                    }
-                    server_prompts.push((server_name.clone(), prompts));
-                }
-                Err(e) => {
-                    server_prompts.push((server_name.clone(), vec![]));
-                    eprintln!(
-                        "{} {}: {}",
-                        "Error listing prompts from".red(),
-                        server_name,
-                        e
-                    );
-                }
-            }
+    fn item_count(&self) -> usize {
+        self.servers.iter().map(|s| s.prompts.len()).sum()
+    }
+
+    fn slice(&self, start: usize, count: usize) -> Self {
+        let servers = slice_groups(
+            &self.servers,
+            |s| &s.prompts,
+            |s, prompts| ServerPrompts { server: s.server.clone(), prompts, error: s.error.clone() },
+            start,
+            count,
+        );
+        // 只保留这一页中出现的冲突
+        let mut conflicts = self.conflicts.clone();
+        conflicts.retain(|key, _| servers.iter().any(|s| s.prompts.iter().any(|i| &i.name == key)));
+        Self { servers, conflicts }
+    }
+}
+
+impl Render for PromptListing {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
+            println!("{}", "No connected servers.".yellow());
+            return Ok(());
         }
 
-        let conflicts: HashMap<&String, &Vec<String>> = prompt_to_servers
-            .iter()
-            .filter(|(_, servers)| servers.len() > 1)
-            .collect();
+        for server in &self.servers {
+            if let Some(e) = &server.error {
+                eprintln!(
+                    "{} {}: {}",
+                    "Error listing prompts from".red(),
+                    server.server,
+                    e
+                );
+            }
+        }
 
-        if !conflicts.is_empty() {
+        if !self.conflicts.is_empty() {
             println!(
                 "{}",
                 "WARNING: Prompt name conflicts detected:".yellow().bold()
             );
-            for (prompt_name, servers) in &conflicts {
+            for (prompt_name, servers) in &self.conflicts {
                 println!(
                     "  '{}' exists in: {}",
                     prompt_name.red(),
@@ -87,18 +121,30 @@ impl ClientManager {
             println!();
         }
 
-        for (server_name, prompts) in &server_prompts {
-            println!("{} {}", "Server:".bold(), server_name.cyan().bold());
-            if prompts.is_empty() {
+        for server in &self.servers {
+            println!("{} {}", "Server:".bold(), server.server.cyan().bold());
+            if server.prompts.is_empty() {
                 println!("  {}", "(No prompts available)".dimmed());
             } else {
-                for (prompt_name, description, args) in prompts {
-                    let conflict_marker = if conflicts.contains_key(prompt_name) {
+                for prompt in &server.prompts {
+                    let conflict_marker = if self.conflicts.contains_key(&prompt.name) {
                         format!(" {}", "[CONFLICT]".red().bold())
                     } else {
                         String::new()
                     };
 
+                    // 必填参数以 * 标记
+                    let args: Vec<String> = prompt
+                        .arguments
+                        .iter()
+                        .map(|arg| {
+                            if arg.required.unwrap_or(false) {
+                                format!("{}*", arg.name)
+                            } else {
+                                arg.name.clone()
+                            }
+                        })
+                        .collect();
                     let args_str = if args.is_empty() {
                         String::new()
                     } else {
@@ -108,10 +154,10 @@ impl ClientManager {
                     println!(
                         "  {} {}{}{}: {}",
                         "-".dimmed(),
-                        prompt_name.green(),
+                        prompt.name.green(),
                         conflict_marker,
                         args_str,
-                        description.dimmed()
+                        prompt.description.dimmed()
                     );
                 }
             }
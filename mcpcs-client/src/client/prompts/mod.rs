@@ -2,6 +2,10 @@ mod list;
 mod info;
 mod use_prompt;
 
+use anyhow::Result;
+
+use crate::client::ClientManager;
+
 pub fn parse_prompt_spec(prompt_spec: &str) -> (Option<&str>, &str) {
     // 如果包含 / 且不是 :// 格式，按第一个 / 分割（server/prompt 格式）
     if !prompt_spec.contains("://") && prompt_spec.contains('/') {
@@ -14,3 +18,11 @@ pub fn parse_prompt_spec(prompt_spec: &str) -> (Option<&str>, &str) {
         (None, prompt_spec)
     }
 }
+
+impl ClientManager {
+    /// 在目录中查找唯一提供该提示词的服务器
+    pub(crate) fn find_prompt_server(&self, prompt_name: &str) -> Result<String> {
+        let found = self.context.catalog.find(None, |c| &c.prompts, |p| p.name == prompt_name);
+        Ok(self.unique_match("Prompt", prompt_name, found)?.0)
+    }
+}
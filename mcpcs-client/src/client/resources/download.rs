@@ -4,128 +4,135 @@ use anyhow::Result;
 use colored::Colorize;
 use base64::Engine;
 use rmcp::model::{ReadResourceRequestParam, ResourceContents};
+use serde::Serialize;
 
-use crate::client::ClientManager;
-use super::parse_resource_spec;
+use crate::client::output::Render;
+use crate::client::{ClientManager, ClientService};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadKind {
+    Text,
+    Binary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceDownload {
+    pub server: String,
+    pub uri: String,
+    pub path: String,
+    pub kind: DownloadKind,
+    /// 文本为字符数，二进制为解码后的字节数
+    pub size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+}
 
 impl ClientManager {
     pub async fn download_resource(&self, resource_spec: &str, local_path: &str) -> Result<()> {
-        let (server_name, resource_uri) = parse_resource_spec(resource_spec);
-        
-        if let Some(server_name) = server_name {
-            // 指定服务器
-            if let Some(client) = self.clients.get(server_name) {
-                return download_resource_from_server(server_name, client, resource_uri, local_path).await;
-            } else {
-                eprintln!("{} {}", "Server not found:".red(), server_name);
-                return Ok(());
-            }
-        }
+        let download = self.save_resource(resource_spec, local_path).await?;
+        self.emit(&download)
+    }
 
-        // 搜索所有服务器
-        let mut found = false;
-        for (server_name, client) in &self.clients {
-            if let Ok(response) = client.list_resources(Default::default()).await {
-                if response.resources.iter().any(|r| r.raw.uri == resource_uri) {
-                    if found {
-                        eprintln!(
-                            "{} '{}' {}",
-                            "Resource".yellow(),
-                            resource_uri,
-                            "found in multiple servers. Use server_name/resource_uri to specify.".yellow()
-                        );
-                        return Ok(());
-                    }
-                    download_resource_from_server(server_name, client, resource_uri, local_path).await?;
-                    found = true;
-                }
-            }
+    pub async fn save_resource(&self, resource_spec: &str, local_path: &str) -> Result<ResourceDownload> {
+        let (server_name, resource_uri) = self.resolve_resource(resource_spec)?;
+        let client = &self.clients[&server_name];
+        self.download_resource_from_server(&server_name, client, &resource_uri, local_path).await
+    }
+
+    async fn download_resource_from_server(
+        &self,
+        server_name: &str,
+        client: &ClientService,
+        resource_uri: &str,
+        local_path: &str,
+    ) -> Result<ResourceDownload> {
+        self.status(format!(
+            "{} {} {} {} {} {}",
+            "Downloading resource".dimmed(),
+            resource_uri.cyan(),
+            "from".dimmed(),
+            server_name.yellow(),
+            "to".dimmed(),
+            local_path.green()
+        ));
+
+        let result = client
+            .read_resource(ReadResourceRequestParam {
+                uri: resource_uri.to_string(),
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Error downloading resource {}: {}", resource_uri, e))?;
+
+        if result.contents.is_empty() {
+            anyhow::bail!("Resource has no content: {}", resource_uri);
         }
 
-        if !found {
-            eprintln!("{} {}", "Resource not found:".red(), resource_uri);
+        // 检查目标目录是否存在
+        if let Some(parent) = Path::new(local_path).parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                self.status(format!("{} {}", "Creating directory:".dimmed(), parent.display()));
+            }
         }
 
-        Ok(())
-    }
-}
+        let (kind, size) = save_contents(local_path, &result.contents[0])?;
 
-async fn download_resource_from_server(
-    server_name: &str,
-    client: &rmcp::service::RunningService<rmcp::RoleClient, ()>,
-    resource_uri: &str,
-    local_path: &str,
-) -> Result<()> {
-    println!(
-        "{} {} {} {} {} {}",
-        "Downloading resource".dimmed(),
-        resource_uri.cyan(),
-        "from".dimmed(),
-        server_name.yellow(),
-        "to".dimmed(),
-        local_path.green()
-    );
-    
-    let response = client
-        .read_resource(ReadResourceRequestParam {
+        Ok(ResourceDownload {
+            server: server_name.to_string(),
             uri: resource_uri.to_string(),
+            path: local_path.to_string(),
+            kind,
+            size,
+            file_size: fs::metadata(local_path).ok().map(|m| m.len()),
         })
-        .await;
-
-    match response {
-        Ok(result) => {
-            if result.contents.is_empty() {
-                eprintln!("{}", "Resource has no content".yellow());
-                return Ok(());
-            }
+    }
+}
 
-            let content = &result.contents[0];
-            
-            // 检查目标目录是否存在
-            if let Some(parent) = Path::new(local_path).parent() {
-                if !parent.exists() {
-                    println!("{} {}", "Creating directory:".dimmed(), parent.display());
-                    fs::create_dir_all(parent)?;
-                }
-            }
+/// 把资源内容写入本地文件 (必要时创建目录)，返回内容类型和大小
+pub(crate) fn save_contents(local_path: &str, content: &ResourceContents) -> Result<(DownloadKind, usize)> {
+    if let Some(parent) = Path::new(local_path).parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            fs::create_dir_all(parent)?;
+        }
+    }
 
-            match content {
-                ResourceContents::TextResourceContents { text, .. } => {
-                    fs::write(local_path, text)?;
-                    println!("{} {} ({} characters)", 
-                        "Downloaded text content to".green(), 
-                        local_path.bold(), 
-                        text.len()
-                    );
-                }
-                ResourceContents::BlobResourceContents { blob, .. } => {
-                    match base64::engine::general_purpose::STANDARD.decode(blob) {
-                        Ok(binary_data) => {
-                            let data_len = binary_data.len();
-                            fs::write(local_path, binary_data)?;
-                            println!("{} {} ({} bytes)", 
-                                "Downloaded binary content to".green(), 
-                                local_path.bold(), 
-                                data_len
-                            );
-                        }
-                        Err(e) => {
-                            eprintln!("{} {}", "Error decoding base64:".red(), e);
-                            return Ok(());
-                        }
-                    }
-                }
-            }
+    Ok(match content {
+        ResourceContents::TextResourceContents { text, .. } => {
+            fs::write(local_path, text)?;
+            (DownloadKind::Text, text.len())
+        }
+        ResourceContents::BlobResourceContents { blob, .. } => {
+            let binary_data = base64::engine::general_purpose::STANDARD
+                .decode(blob)
+                .map_err(|e| anyhow::anyhow!("Error decoding base64: {}", e))?;
+            let data_len = binary_data.len();
+            fs::write(local_path, binary_data)?;
+            (DownloadKind::Binary, data_len)
+        }
+    })
+}
 
-            // 显示文件信息
-            if let Ok(metadata) = fs::metadata(local_path) {
-                println!("{} {} bytes", "File size:".dimmed(), metadata.len());
-            }
+impl Render for ResourceDownload {
+    fn render_text(&self) -> Result<()> {
+        match self.kind {
+            DownloadKind::Text => println!(
+                "{} {} ({} characters)",
+                "Downloaded text content to".green(),
+                self.path.bold(),
+                self.size
+            ),
+            DownloadKind::Binary => println!(
+                "{} {} ({} bytes)",
+                "Downloaded binary content to".green(),
+                self.path.bold(),
+                self.size
+            ),
         }
-        Err(e) => {
-            eprintln!("{} {}: {}", "Error downloading resource".red(), resource_uri, e);
+
+        // 显示文件信息
+        if let Some(file_size) = self.file_size {
+            println!("{} {} bytes", "File size:".dimmed(), file_size);
         }
+        Ok(())
     }
-
-    Ok(())
 }
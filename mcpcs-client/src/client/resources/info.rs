@@ -1,59 +1,103 @@
 use anyhow::Result;
 use colored::Colorize;
-use rmcp::model::Resource;
+use rmcp::model::{Resource, ResourceTemplate};
+use serde::Serialize;
 
-use crate::client::ClientManager;
-use super::parse_resource_spec;
+use crate::client::output::Render;
+use crate::client::{ClientError, ClientManager};
+use super::UriTemplate;
+
+#[derive(Debug, Serialize)]
+pub struct ResourceDetails {
+    pub server: String,
+    pub resource: Resource,
+}
 
 impl ClientManager {
-    pub async fn resource_info(&self, resource_spec: &str) -> Result<()> {
-        let (server_name, resource_uri) = parse_resource_spec(resource_spec);
-        
-        if let Some(server_name) = server_name {
-            // 指定服务器
-            if let Some(client) = self.clients.get(server_name) {
-                if let Ok(response) = client.list_resources(Default::default()).await {
-                    if let Some(resource) = response.resources.iter().find(|r| r.raw.uri == resource_uri) {
-                        print_resource_info(server_name, resource)?;
-                    } else {
-                        eprintln!("{} {} {} {}", "Resource".red(), resource_uri, "not found on server".red(), server_name);
-                    }
-                }
-                return Ok(());
-            } else {
-                eprintln!("{} {}", "Server not found:".red(), server_name);
-                return Ok(());
+    pub fn resource_info(&self, resource_spec: &str) -> Result<()> {
+        let details = self.resource_details(resource_spec)?;
+        self.emit(&details)
+    }
+
+    pub fn resource_details(&self, resource_spec: &str) -> Result<ResourceDetails> {
+        let (server_name, resource_uri) = self.resolve_resource(resource_spec)?;
+
+        let listed = self.context.catalog.get(&server_name).resources;
+        match listed.items.into_iter().find(|r| r.raw.uri == resource_uri) {
+            Some(resource) => Ok(ResourceDetails { server: server_name, resource }),
+            None => Err(ClientError::NotFound {
+                kind: "Resource",
+                name: format!("{}/{}", server_name, resource_uri),
             }
+            .into()),
         }
+    }
+}
 
-        // 搜索所有服务器
-        let mut found = false;
-        for (server_name, client) in &self.clients {
-            if let Ok(response) = client.list_resources(Default::default()).await {
-                if let Some(resource) = response.resources.iter().find(|r| r.raw.uri == resource_uri) {
-                    if found {
-                        eprintln!(
-                            "{} '{}' {}",
-                            "Resource".yellow(),
-                            resource_uri,
-                            "found in multiple servers. Use server_name/resource_uri to specify.".yellow()
-                        );
-                        return Ok(());
-                    }
-                    print_resource_info(server_name, resource)?;
-                    found = true;
-                }
-            }
+#[derive(Debug, Serialize)]
+pub struct TemplateDetails {
+    pub server: String,
+    pub template: ResourceTemplate,
+    /// 从 URI 模板中解析出的变量名
+    pub variables: Vec<String>,
+}
+
+impl ClientManager {
+    pub fn template_info(&self, template_spec: &str) -> Result<()> {
+        let details = self.template_details(template_spec)?;
+        self.emit(&details)
+    }
+
+    pub fn template_details(&self, template_spec: &str) -> Result<TemplateDetails> {
+        let (server, template) = self.resolve_template(template_spec)?;
+        // 无法解析的模板仍然显示，只是没有变量列表
+        let variables = UriTemplate::parse(&template.raw.uri_template)
+            .map(|t| t.variables().into_iter().map(String::from).collect())
+            .unwrap_or_default();
+        Ok(TemplateDetails { server, template, variables })
+    }
+}
+
+impl Render for TemplateDetails {
+    fn render_text(&self) -> Result<()> {
+        let template = &self.template.raw;
+        println!("{}", "━".repeat(60).dimmed());
+        println!("{} {}", "Server:".bold(), self.server.yellow());
+        println!("{} {}", "Template:".bold(), template.name.green());
+        println!("{} {}", "URI Template:".bold(), template.uri_template.cyan());
+
+        if let Some(title) = &template.title {
+            println!("{} {}", "Title:".bold(), title);
+        }
+
+        if let Some(description) = &template.description {
+            println!("{} {}", "Description:".bold(), description.dimmed());
+        }
+
+        if let Some(mime_type) = &template.mime_type {
+            println!("{} {}", "MIME Type:".bold(), mime_type.cyan());
         }
 
-        if !found {
-            eprintln!("{} {}", "Resource not found:".red(), resource_uri);
+        if !self.variables.is_empty() {
+            println!("{} {}", "Variables:".bold(), self.variables.join(", ").green());
+            let example: Vec<String> = self.variables.iter().map(|v| format!("{}=...", v)).collect();
+            println!(
+                "  {}",
+                format!("Read with: /read resource {}/{} {}", self.server, template.name, example.join(" ")).dimmed()
+            );
         }
 
+        println!("{}", "━".repeat(60).dimmed());
         Ok(())
     }
 }
 
+impl Render for ResourceDetails {
+    fn render_text(&self) -> Result<()> {
+        print_resource_info(&self.server, &self.resource)
+    }
+}
+
 fn print_resource_info(server_name: &str, resource: &Resource) -> Result<()> {
     println!("{}", "━".repeat(60).dimmed());
     println!("{} {}", "Server:".bold(), server_name.yellow());
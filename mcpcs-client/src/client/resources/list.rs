@@ -1,66 +1,115 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 
+use crate::client::output::{find_conflicts, Render};
+use crate::client::paging::{slice_groups, ListView, Paged};
 use crate::client::ClientManager;
 
+#[derive(Debug, Serialize)]
+pub struct ResourceListing {
+    pub servers: Vec<ServerResources>,
+    /// 资源 URI -> 提供该资源的服务器 (仅包含冲突的资源)
+    pub conflicts: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerResources {
+    pub server: String,
+    pub resources: Vec<ResourceSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceSummary {
+    pub uri: String,
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
 impl ClientManager {
-    pub async fn list_resources(&self) -> Result<()> {
-        if self.clients.is_empty() {
+    pub async fn list_resources(&self, view: ListView) -> Result<()> {
+        let listing = self.resource_listing();
+        self.emit_list(listing, view).await
+    }
+
+    pub fn resource_listing(&self) -> ResourceListing {
+        let mut servers = Vec::new();
+
+        for server_name in self.list_servers() {
+            let listed = self.context.catalog.get(&server_name).resources;
+            let resources = listed
+                .items
+                .iter()
+                .map(|r| ResourceSummary {
+                    uri: r.raw.uri.clone(),
+                    name: r.raw.name.clone(),
+                    description: r.raw.description.clone().unwrap_or_default(),
+                    mime_type: r.raw.mime_type.clone(),
+                })
+                .collect();
+            servers.push(ServerResources { server: server_name, resources, error: listed.error });
+        }
+
+        let conflicts = find_conflicts(
+            servers
+                .iter()
+                .flat_map(|s| s.resources.iter().map(move |r| (s.server.as_str(), r.uri.as_str()))),
+        );
+        ResourceListing { servers, conflicts }
+    }
+}
+
+impl Paged for ResourceListing {
+    const ITEMS: &'static str = "resources";
+
+    fn item_count(&self) -> usize {
+        self.servers.iter().map(|s| s.resources.len()).sum()
+    }
+
+    fn slice(&self, start: usize, count: usize) -> Self {
+        let servers = slice_groups(
+            &self.servers,
+            |s| &s.resources,
+            |s, resources| ServerResources { server: s.server.clone(), resources, error: s.error.clone() },
+            start,
+            count,
+        );
+        // 只保留这一页中出现的冲突
+        let mut conflicts = self.conflicts.clone();
+        conflicts.retain(|key, _| servers.iter().any(|s| s.resources.iter().any(|i| &i.uri == key)));
+        Self { servers, conflicts }
+    }
+}
+
+impl Render for ResourceListing {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
             println!("{}", "No connected servers.".yellow());
             return Ok(());
         }
 
-        let mut resource_to_servers: HashMap<String, Vec<String>> = HashMap::new();
-        let mut server_resources: Vec<(String, Vec<(String, String, String)>)> = Vec::new();
-
-        for (server_name, client) in &self.clients {
-            let result = client.list_resources(Default::default()).await;
-            match result {
-                Ok(response) => {
-                    let resources: Vec<(String, String, String)> = response
-                        .resources
-                        .iter()
-                        .map(|r| {
-                            (
-                                r.raw.uri.clone(),
-                                r.raw.name.clone(),
-                                r.raw.description.clone().unwrap_or_default(),
-                            )
-                        })
-                        .collect();
-
-                    for (resource_uri, _, _) in &resources {
-                        resource_to_servers
-                            .entry(resource_uri.clone())
-                            .or_default()
-                            .push(server_name.clone());
-                    }
-                    server_resources.push((server_name.clone(), resources));
-                }
-                Err(e) => {
-                    server_resources.push((server_name.clone(), vec![]));
-                    eprintln!(
-                        "{} {}: {}",
-                        "Error listing resources from".red(),
-                        server_name,
-                        e
-                    );
-                }
+        for server in &self.servers {
+            if let Some(e) = &server.error {
+                eprintln!(
+                    "{} {}: {}",
+                    "Error listing resources from".red(),
+                    server.server,
+                    e
+                );
             }
         }
 
-        let conflicts: HashMap<&String, &Vec<String>> = resource_to_servers
-            .iter()
-            .filter(|(_, servers)| servers.len() > 1)
-            .collect();
-
-        if !conflicts.is_empty() {
+        if !self.conflicts.is_empty() {
             println!(
                 "{}",
                 "WARNING: Resource URI conflicts detected:".yellow().bold()
             );
-            for (resource_uri, servers) in &conflicts {
+            for (resource_uri, servers) in &self.conflicts {
                 println!(
                     "  '{}' exists in: {}",
                     resource_uri.red(),
@@ -74,28 +123,28 @@ impl ClientManager {
             println!();
         }
 
-        for (server_name, resources) in &server_resources {
-            println!("{} {}", "Server:".bold(), server_name.cyan().bold());
-            if resources.is_empty() {
+        for server in &self.servers {
+            println!("{} {}", "Server:".bold(), server.server.cyan().bold());
+            if server.resources.is_empty() {
                 println!("  {}", "(No resources available)".dimmed());
             } else {
-                for (resource_uri, resource_name, description) in resources {
-                    if conflicts.contains_key(resource_uri) {
+                for resource in &server.resources {
+                    if self.conflicts.contains_key(&resource.uri) {
                         println!(
                             "  {} {} {} {}: {}",
                             "-".dimmed(),
-                            resource_name.green(),
+                            resource.name.green(),
                             "[CONFLICT]".red().bold(),
-                            format!("({})", resource_uri).blue(),
-                            description.dimmed()
+                            format!("({})", resource.uri).blue(),
+                            resource.description.dimmed()
                         );
                     } else {
                         println!(
                             "  {} {} {}: {}",
                             "-".dimmed(),
-                            resource_name.green(),
-                            format!("({})", resource_uri).blue(),
-                            description.dimmed()
+                            resource.name.green(),
+                            format!("({})", resource.uri).blue(),
+                            resource.description.dimmed()
                         );
                     }
                 }
@@ -104,3 +153,145 @@ impl ClientManager {
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct TemplateListing {
+    pub servers: Vec<ServerTemplates>,
+    /// 模板名 -> 提供该模板的服务器 (仅包含冲突的模板)
+    pub conflicts: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerTemplates {
+    pub server: String,
+    pub templates: Vec<TemplateSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub uri_template: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl ClientManager {
+    pub async fn list_templates(&self, view: ListView) -> Result<()> {
+        let listing = self.template_listing();
+        self.emit_list(listing, view).await
+    }
+
+    pub fn template_listing(&self) -> TemplateListing {
+        let mut servers = Vec::new();
+
+        for server_name in self.list_servers() {
+            let listed = self.context.catalog.get(&server_name).templates;
+            let templates = listed
+                .items
+                .into_iter()
+                .map(|t| TemplateSummary {
+                    name: t.raw.name,
+                    uri_template: t.raw.uri_template,
+                    description: t.raw.description.unwrap_or_default(),
+                    mime_type: t.raw.mime_type,
+                })
+                .collect();
+            servers.push(ServerTemplates { server: server_name, templates, error: listed.error });
+        }
+
+        let conflicts = find_conflicts(
+            servers
+                .iter()
+                .flat_map(|s| s.templates.iter().map(move |t| (s.server.as_str(), t.name.as_str()))),
+        );
+        TemplateListing { servers, conflicts }
+    }
+}
+
+impl Paged for TemplateListing {
+    const ITEMS: &'static str = "templates";
+
+    fn item_count(&self) -> usize {
+        self.servers.iter().map(|s| s.templates.len()).sum()
+    }
+
+    fn slice(&self, start: usize, count: usize) -> Self {
+        let servers = slice_groups(
+            &self.servers,
+            |s| &s.templates,
+            |s, templates| ServerTemplates { server: s.server.clone(), templates, error: s.error.clone() },
+            start,
+            count,
+        );
+        // 只保留这一页中出现的冲突
+        let mut conflicts = self.conflicts.clone();
+        conflicts.retain(|key, _| servers.iter().any(|s| s.templates.iter().any(|i| &i.name == key)));
+        Self { servers, conflicts }
+    }
+}
+
+impl Render for TemplateListing {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
+            println!("{}", "No connected servers.".yellow());
+            return Ok(());
+        }
+
+        for server in &self.servers {
+            if let Some(e) = &server.error {
+                eprintln!(
+                    "{} {}: {}",
+                    "Error listing resource templates from".red(),
+                    server.server,
+                    e
+                );
+            }
+        }
+
+        if !self.conflicts.is_empty() {
+            println!(
+                "{}",
+                "WARNING: Resource template name conflicts detected:".yellow().bold()
+            );
+            for (template_name, servers) in &self.conflicts {
+                println!(
+                    "  '{}' exists in: {}",
+                    template_name.red(),
+                    servers.join(", ").cyan()
+                );
+            }
+            println!(
+                "  {}",
+                "Use /read resource server_name/template_name key=value to specify which server.".dimmed()
+            );
+            println!();
+        }
+
+        for server in &self.servers {
+            println!("{} {}", "Server:".bold(), server.server.cyan().bold());
+            if server.templates.is_empty() {
+                println!("  {}", "(No resource templates available)".dimmed());
+            } else {
+                for template in &server.templates {
+                    let conflict_marker = if self.conflicts.contains_key(&template.name) {
+                        format!(" {}", "[CONFLICT]".red().bold())
+                    } else {
+                        String::new()
+                    };
+                    println!(
+                        "  {} {}{} {}: {}",
+                        "-".dimmed(),
+                        template.name.green(),
+                        conflict_marker,
+                        format!("({})", template.uri_template).blue(),
+                        template.description.dimmed()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,15 +2,57 @@ mod list;
 mod read;
 mod info;
 mod download;
+mod subscribe;
+mod uri_template;
+
+pub use subscribe::SubscriptionSet;
+pub(crate) use subscribe::render_diff;
+pub use uri_template::UriTemplate;
+
+use anyhow::Result;
+use rmcp::model::ResourceTemplate;
+
+use crate::client::{ClientError, ClientManager};
 
 pub fn parse_resource_spec(resource_spec: &str) -> (Option<&str>, &str) {
-    // 如果包含 :// 说明是 URI，不应该按 / 分割
-    if resource_spec.contains("://") {
-        (None, resource_spec)
-    } else if let Some(pos) = resource_spec.find('/') {
-        // 只有在不是 URI 的情况下才按 / 分割（server/uri 格式）
-        (Some(&resource_spec[..pos]), &resource_spec[pos + 1..])
-    } else {
-        (None, resource_spec)
+    match resource_spec.find('/') {
+        // 第一个 / 之前含 : 说明整体是 URI (如 file:///x)，否则是 server/uri 格式 (如 fs/file:///x)
+        Some(pos) if !resource_spec[..pos].contains(':') => {
+            (Some(&resource_spec[..pos]), &resource_spec[pos + 1..])
+        }
+        _ => (None, resource_spec),
+    }
+}
+
+impl ClientManager {
+    /// 解析 `server/uri`，未指定服务器时在目录中查找，返回 (服务器, URI)
+    pub(crate) fn resolve_resource(&self, resource_spec: &str) -> Result<(String, String)> {
+        match parse_resource_spec(resource_spec) {
+            (Some(server_name), uri) if self.clients.contains_key(server_name) => {
+                Ok((server_name.to_string(), uri.to_string()))
+            }
+            (Some(server_name), _) => Err(ClientError::ServerNotFound(server_name.to_string()).into()),
+            (None, uri) => {
+                let found = self.context.catalog.find(None, |c| &c.resources, |r| r.raw.uri == uri);
+                let (server_name, _) = self.unique_match("Resource", uri, found)?;
+                Ok((server_name, uri.to_string()))
+            }
+        }
+    }
+
+    /// 解析 `server/模板`，模板可以写名称或 URI 模板本身；未指定服务器时在目录中查找
+    pub(crate) fn resolve_template(&self, template_spec: &str) -> Result<(String, ResourceTemplate)> {
+        let (server, name) = parse_resource_spec(template_spec);
+        if let Some(server_name) = server {
+            if !self.clients.contains_key(server_name) {
+                return Err(ClientError::ServerNotFound(server_name.to_string()).into());
+            }
+        }
+        let found = self.context.catalog.find(
+            server,
+            |c| &c.templates,
+            |t| t.raw.name == name || t.raw.uri_template == name,
+        );
+        self.unique_match("Resource template", name, found)
     }
 }
@@ -1,110 +1,157 @@
+use std::collections::BTreeMap;
 use anyhow::Result;
 use colored::Colorize;
 use base64::Engine;
-use rmcp::model::{ReadResourceRequestParam, ResourceContents};
+use rmcp::model::{ReadResourceRequestParam, ReadResourceResult, ResourceContents};
+use rmcp::service::Peer;
+use rmcp::RoleClient;
+use serde::Serialize;
 
-use crate::client::ClientManager;
-use super::parse_resource_spec;
+use crate::client::output::Render;
+use crate::client::{ClientError, ClientManager, ClientService};
+use super::UriTemplate;
+
+#[derive(Debug, Serialize)]
+pub struct ResourceRead {
+    pub server: String,
+    pub uri: String,
+    pub result: ReadResourceResult,
+}
 
 impl ClientManager {
     pub async fn read_resource(&self, resource_spec: &str) -> Result<()> {
-        let (server_name, resource_uri) = parse_resource_spec(resource_spec);
-        
-        if let Some(server_name) = server_name {
-            // 指定服务器
-            if let Some(client) = self.clients.get(server_name) {
-                return read_resource_from_server(server_name, client, resource_uri).await;
-            } else {
-                eprintln!("{} {}", "Server not found:".red(), server_name);
-                return Ok(());
-            }
-        }
+        let read = self.fetch_resource(resource_spec).await?;
+        self.emit(&read)
+    }
 
-        // 搜索所有服务器
-        let mut found = false;
-        for (server_name, client) in &self.clients {
-            if let Ok(response) = client.list_resources(Default::default()).await {
-                if response.resources.iter().any(|r| r.raw.uri == resource_uri) {
-                    if found {
-                        eprintln!(
-                            "{} '{}' {}",
-                            "Resource".yellow(),
-                            resource_uri,
-                            "found in multiple servers. Use server_name/resource_uri to specify.".yellow()
-                        );
-                        return Ok(());
-                    }
-                    read_resource_from_server(server_name, client, resource_uri).await?;
-                    found = true;
-                }
-            }
-        }
+    pub async fn fetch_resource(&self, resource_spec: &str) -> Result<ResourceRead> {
+        let (server_name, resource_uri) = self.resolve_resource(resource_spec)?;
+        let client = &self.clients[&server_name];
+        self.read_resource_from_server(&server_name, client, &resource_uri).await
+    }
+
+    /// 用 key=value 参数展开资源模板，再按普通资源读取
+    pub async fn read_template(&self, template_spec: &str, params: &[&str]) -> Result<()> {
+        let read = self.fetch_template(template_spec, params).await?;
+        self.emit(&read)
+    }
 
-        if !found {
-            eprintln!("{} {}", "Resource not found:".red(), resource_uri);
+    pub async fn fetch_template(&self, template_spec: &str, params: &[&str]) -> Result<ResourceRead> {
+        let (server_name, template) = self.resolve_template(template_spec)?;
+        let uri = expand_template(&template.raw.uri_template, params)?;
+        let client = &self.clients[&server_name];
+        self.read_resource_from_server(&server_name, client, &uri).await
+    }
+
+    pub(crate) async fn read_resource_from_server(
+        &self,
+        server_name: &str,
+        client: &ClientService,
+        resource_uri: &str,
+    ) -> Result<ResourceRead> {
+        self.status(format!(
+            "{} {} {} {}",
+            "Reading resource".dimmed(),
+            resource_uri.cyan(),
+            "from".dimmed(),
+            server_name.yellow()
+        ));
+        read_from_peer(server_name, client, resource_uri).await
+    }
+}
+
+/// 检查参数都是模板中的变量、路径部分的变量都已给出，然后展开
+fn expand_template(uri_template: &str, params: &[&str]) -> Result<String> {
+    let template = UriTemplate::parse(uri_template)
+        .map_err(|e| anyhow::anyhow!("Invalid URI template '{}': {}", uri_template, e))?;
+    let variables = template.variables();
+
+    let mut values = BTreeMap::new();
+    for param in params {
+        let (key, value) = param
+            .split_once('=')
+            .ok_or_else(|| ClientError::Usage(format!("Expected key=value, got '{}'", param)))?;
+        if !variables.contains(&key) {
+            return Err(ClientError::Usage(format!(
+                "Unknown variable '{}' for template {} (variables: {})",
+                key,
+                uri_template,
+                variables.join(", ")
+            ))
+            .into());
         }
+        values.insert(key.to_string(), value.to_string());
+    }
 
-        Ok(())
+    let missing: Vec<&str> = template
+        .required_variables()
+        .into_iter()
+        .filter(|v| !values.contains_key(*v))
+        .collect();
+    if !missing.is_empty() {
+        return Err(ClientError::Usage(format!(
+            "Missing value for {} in template {}",
+            missing.join(", "),
+            uri_template
+        ))
+        .into());
     }
+    Ok(template.expand(&values))
 }
 
-async fn read_resource_from_server(
+/// 不依赖 manager 的读取，handler 收到资源更新通知时也走这里
+pub(crate) async fn read_from_peer(
     server_name: &str,
-    client: &rmcp::service::RunningService<rmcp::RoleClient, ()>,
+    peer: &Peer<RoleClient>,
     resource_uri: &str,
-) -> Result<()> {
-    println!(
-        "{} {} {} {}",
-        "Reading resource".dimmed(),
-        resource_uri.cyan(),
-        "from".dimmed(),
-        server_name.yellow()
-    );
-    
-    let response = client
+) -> Result<ResourceRead> {
+    let result = peer
         .read_resource(ReadResourceRequestParam {
             uri: resource_uri.to_string(),
         })
-        .await;
+        .await
+        .map_err(|e| anyhow::anyhow!("Error reading resource {}: {}", resource_uri, e))?;
 
-    match response {
-        Ok(result) => {
-            for content in result.contents {
-                println!("{}", "━".repeat(60).dimmed());
-                
-                match &content {
-                    ResourceContents::TextResourceContents { uri, mime_type, text, .. } => {
-                        println!("{} {}", "URI:".bold(), uri.yellow());
-                        if let Some(mime_type) = mime_type {
-                            println!("{} {}", "MIME Type:".bold(), mime_type.cyan());
-                        }
-                        println!("{}", "━".repeat(60).dimmed());
-                        println!("{}", text);
+    Ok(ResourceRead {
+        server: server_name.to_string(),
+        uri: resource_uri.to_string(),
+        result,
+    })
+}
+
+impl Render for ResourceRead {
+    fn render_text(&self) -> Result<()> {
+        for content in &self.result.contents {
+            println!("{}", "━".repeat(60).dimmed());
+
+            match content {
+                ResourceContents::TextResourceContents { uri, mime_type, text, .. } => {
+                    println!("{} {}", "URI:".bold(), uri.yellow());
+                    if let Some(mime_type) = mime_type {
+                        println!("{} {}", "MIME Type:".bold(), mime_type.cyan());
+                    }
+                    println!("{}", "━".repeat(60).dimmed());
+                    println!("{}", text);
+                }
+                ResourceContents::BlobResourceContents { uri, mime_type, blob, .. } => {
+                    println!("{} {}", "URI:".bold(), uri.yellow());
+                    if let Some(mime_type) = mime_type {
+                        println!("{} {}", "MIME Type:".bold(), mime_type.cyan());
                     }
-                    ResourceContents::BlobResourceContents { uri, mime_type, blob, .. } => {
-                        println!("{} {}", "URI:".bold(), uri.yellow());
-                        if let Some(mime_type) = mime_type {
-                            println!("{} {}", "MIME Type:".bold(), mime_type.cyan());
+                    println!("{}", "━".repeat(60).dimmed());
+                    match base64::engine::general_purpose::STANDARD.decode(blob) {
+                        Ok(binary_data) => {
+                            println!("{} {} bytes", "Binary content:".cyan(), binary_data.len());
+                            println!("{}", "Use appropriate tools to view binary content".dimmed());
                         }
-                        println!("{}", "━".repeat(60).dimmed());
-                        match base64::engine::general_purpose::STANDARD.decode(blob) {
-                            Ok(binary_data) => {
-                                println!("{} {} bytes", "Binary content:".cyan(), binary_data.len());
-                                println!("{}", "Use appropriate tools to view binary content".dimmed());
-                            }
-                            Err(e) => {
-                                eprintln!("{} {}", "Error decoding base64:".red(), e);
-                            }
+                        Err(e) => {
+                            eprintln!("{} {}", "Error decoding base64:".red(), e);
                         }
                     }
                 }
-                println!();
             }
+            println!();
         }
-        Err(e) => {
-            eprintln!("{} {}: {}", "Error reading resource".red(), resource_uri, e);
-        }
+        Ok(())
     }
-
-    Ok(())
 }
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::{ResourceContents, SubscribeRequestParam, UnsubscribeRequestParam};
+use rmcp::service::Peer;
+use rmcp::RoleClient;
+use serde::Serialize;
+
+use crate::client::output::Render;
+use crate::client::{ClientError, ClientManager};
+use crate::console;
+use super::download::save_contents;
+use super::parse_resource_spec;
+use super::read::{read_from_peer, ResourceRead};
+
+/// 已订阅的资源，以 (服务器, URI) 为键
+#[derive(Default)]
+pub struct SubscriptionSet {
+    entries: Mutex<BTreeMap<(String, String), Subscription>>,
+}
+
+struct Subscription {
+    /// 绑定的本地文件，更新时写入文件而不是显示差异
+    file: Option<String>,
+    /// 上一次读取到的文本，用于计算差异
+    last: String,
+    updates: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionSummary {
+    pub server: String,
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub updates: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionListing {
+    pub subscriptions: Vec<SubscriptionSummary>,
+}
+
+impl SubscriptionSet {
+    /// 服务器断开时丢弃它的订阅
+    pub fn remove_server(&self, server: &str) {
+        self.entries.lock().unwrap().retain(|(s, _), _| s != server);
+    }
+
+    /// 收到 `notifications/resources/updated` 时重新读取已订阅的资源；未订阅时返回 false
+    pub async fn on_updated(&self, server: &str, peer: &Peer<RoleClient>, uri: &str) -> bool {
+        let key = (server.to_string(), uri.to_string());
+        let file = match self.entries.lock().unwrap().get(&key) {
+            Some(sub) => sub.file.clone(),
+            None => return false,
+        };
+
+        let console = console::global();
+        let header = format!("[{}] {} {}", server.cyan(), "resource updated:".yellow(), uri);
+        let read = match read_from_peer(server, peer, uri).await {
+            Ok(read) => read,
+            Err(e) => {
+                console.print_above(&format!("{} {} {:#}", header, "re-read failed:".red(), e));
+                return true;
+            }
+        };
+
+        let text = snapshot(&read);
+        let previous = {
+            let mut entries = self.entries.lock().unwrap();
+            let Some(sub) = entries.get_mut(&key) else {
+                return true;
+            };
+            sub.updates += 1;
+            std::mem::replace(&mut sub.last, text.clone())
+        };
+
+        match (&file, read.result.contents.first()) {
+            (Some(path), Some(content)) => match save_contents(path, content) {
+                Ok((_, size)) => console.print_above(&format!("{} {} {} ({})", header, "saved to".dimmed(), path.bold(), size)),
+                Err(e) => console.print_above(&format!("{} {} {:#}", header, "save failed:".red(), e)),
+            },
+            _ => {
+                let diff = render_diff(&previous, &text);
+                if diff.is_empty() {
+                    console.print_above(&format!("{} {}", header, "(no changes)".dimmed()));
+                } else {
+                    console.print_above(&format!("{}\n{}", header, diff));
+                }
+            }
+        }
+        true
+    }
+
+    /// 重连后服务器端的订阅已丢失，重新发送 subscribe
+    pub async fn restore(&self, server: &str, peer: &Peer<RoleClient>) {
+        let uris: Vec<String> = self
+            .entries
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(s, _)| s == server)
+            .map(|(_, uri)| uri.clone())
+            .collect();
+        for uri in uris {
+            if let Err(e) = peer.subscribe(SubscribeRequestParam { uri: uri.clone() }).await {
+                console::global().print_above(&format!(
+                    "[{}] {} {}: {}",
+                    server.cyan(),
+                    "re-subscribe failed for".red(),
+                    uri,
+                    e
+                ));
+            }
+        }
+    }
+}
+
+impl ClientManager {
+    pub async fn subscribe_resource(&self, resource_spec: &str, file: Option<&str>) -> Result<()> {
+        let (server_name, uri) = self.resolve_resource(resource_spec)?;
+        let client = &self.clients[&server_name];
+
+        let supported = client
+            .peer_info()
+            .and_then(|info| info.capabilities.resources.as_ref())
+            .and_then(|r| r.subscribe)
+            .unwrap_or(false);
+        if !supported {
+            anyhow::bail!("Server '{}' does not support resource subscriptions", server_name);
+        }
+
+        client
+            .subscribe(SubscribeRequestParam { uri: uri.clone() })
+            .await
+            .map_err(|e| anyhow::anyhow!("Error subscribing to {}: {}", uri, e))?;
+
+        // 先读一次作为比较基准，绑定了文件时同时写入
+        let read = self.read_resource_from_server(&server_name, client, &uri).await?;
+        if let (Some(path), Some(content)) = (file, read.result.contents.first()) {
+            save_contents(path, content)?;
+        }
+        self.context.subscriptions.entries.lock().unwrap().insert(
+            (server_name.clone(), uri.clone()),
+            Subscription {
+                file: file.map(str::to_string),
+                last: snapshot(&read),
+                updates: 0,
+            },
+        );
+
+        self.status(format!("{} {}/{}", "Subscribed to".green(), server_name.cyan(), uri));
+        Ok(())
+    }
+
+    pub async fn unsubscribe_resource(&self, resource_spec: &str) -> Result<()> {
+        let (server_name, uri) = parse_resource_spec(resource_spec);
+        let key = {
+            let entries = self.context.subscriptions.entries.lock().unwrap();
+            let mut matches: Vec<&(String, String)> = entries
+                .keys()
+                .filter(|(s, u)| u == uri && server_name.is_none_or(|n| n == s))
+                .collect();
+            match matches.len() {
+                0 => {
+                    return Err(ClientError::NotFound {
+                        kind: "Subscription",
+                        name: resource_spec.to_string(),
+                    }
+                    .into())
+                }
+                1 => matches.remove(0).clone(),
+                _ => {
+                    return Err(ClientError::Conflict {
+                        kind: "Subscription",
+                        name: uri.to_string(),
+                        servers: matches.iter().map(|(s, _)| s.clone()).collect(),
+                    }
+                    .into())
+                }
+            }
+        };
+
+        if let Some(client) = self.clients.get(&key.0) {
+            client
+                .unsubscribe(UnsubscribeRequestParam { uri: key.1.clone() })
+                .await
+                .map_err(|e| anyhow::anyhow!("Error unsubscribing from {}: {}", key.1, e))?;
+        }
+        self.context.subscriptions.entries.lock().unwrap().remove(&key);
+
+        self.status(format!("{} {}/{}", "Unsubscribed from".green(), key.0.cyan(), key.1));
+        Ok(())
+    }
+
+    pub fn subscription_listing(&self) -> SubscriptionListing {
+        let entries = self.context.subscriptions.entries.lock().unwrap();
+        SubscriptionListing {
+            subscriptions: entries
+                .iter()
+                .map(|((server, uri), sub)| SubscriptionSummary {
+                    server: server.clone(),
+                    uri: uri.clone(),
+                    file: sub.file.clone(),
+                    updates: sub.updates,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 资源内容的文本快照；二进制内容只记录大小
+fn snapshot(read: &ResourceRead) -> String {
+    read.result
+        .contents
+        .iter()
+        .map(|content| match content {
+            ResourceContents::TextResourceContents { text, .. } => text.clone(),
+            ResourceContents::BlobResourceContents { blob, .. } => {
+                format!("[binary content, {} base64 characters]", blob.len())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 基于最长公共子序列的逐行差异，只输出变化的行
+pub(crate) fn render_diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // 内容过大时不做 LCS，直接显示新内容
+    if old.len() * new.len() > 4_000_000 {
+        return new.iter().map(|l| format!("+ {}", l).green().to_string()).collect::<Vec<_>>().join("\n");
+    }
+
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", old[i]).red().to_string());
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]).green().to_string());
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+impl Render for SubscriptionListing {
+    fn render_text(&self) -> Result<()> {
+        if self.subscriptions.is_empty() {
+            println!("{}", "No active subscriptions".dimmed());
+            return Ok(());
+        }
+        println!("{}", "Subscriptions:".bold());
+        for sub in &self.subscriptions {
+            let target = match &sub.file {
+                Some(file) => format!("-> {}", file),
+                None => "(diff)".to_string(),
+            };
+            println!(
+                "  {}/{} {} {}",
+                sub.server.cyan(),
+                sub.uri,
+                target.dimmed(),
+                format!("[{} updates]", sub.updates).dimmed()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ReadResourceResult;
+
+    fn diff(old: &str, new: &str) -> Vec<String> {
+        colored::control::set_override(false);
+        render_diff(old, new).lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn diff_shows_only_changed_lines() {
+        assert_eq!(diff("a\nb\nc", "a\nB\nc"), ["- b", "+ B"]);
+        assert!(diff("a\nb", "a\nb").is_empty());
+    }
+
+    #[test]
+    fn diff_shows_insertions_and_removals() {
+        assert_eq!(diff("a\nc", "a\nb\nc"), ["+ b"]);
+        assert_eq!(diff("a\nb\nc", "a\nc"), ["- b"]);
+        assert_eq!(diff("", "x"), ["+ x"]);
+    }
+
+    #[test]
+    fn snapshot_records_blob_size() {
+        let read = ResourceRead {
+            server: "s".to_string(),
+            uri: "file:///x".to_string(),
+            result: ReadResourceResult {
+                contents: vec![
+                    ResourceContents::text("hello", "file:///x"),
+                    ResourceContents::BlobResourceContents {
+                        uri: "file:///x".to_string(),
+                        mime_type: None,
+                        blob: "AAAA".to_string(),
+                        meta: None,
+                    },
+                ],
+            },
+        };
+        assert_eq!(snapshot(&read), "hello\n[binary content, 4 base64 characters]");
+    }
+}
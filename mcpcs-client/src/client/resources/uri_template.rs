@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+
+/// RFC 6570 URI 模板 (到 Level 4，不支持关联数组)，在客户端展开后按普通资源读取
+#[derive(Debug, Clone)]
+pub struct UriTemplate {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Literal(String),
+    Expression { op: Operator, vars: Vec<VarSpec> },
+}
+
+#[derive(Debug, Clone)]
+struct VarSpec {
+    name: String,
+    explode: bool,
+    prefix: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Simple,
+    Reserved,
+    Fragment,
+    Label,
+    Path,
+    PathParam,
+    Query,
+    QueryContinuation,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '+' => Operator::Reserved,
+            '#' => Operator::Fragment,
+            '.' => Operator::Label,
+            '/' => Operator::Path,
+            ';' => Operator::PathParam,
+            '?' => Operator::Query,
+            '&' => Operator::QueryContinuation,
+            _ => return None,
+        })
+    }
+
+    /// (前缀, 分隔符, 是否带变量名, 空值时变量名后的内容, 是否保留 reserved 字符)
+    fn rules(self) -> (&'static str, &'static str, bool, &'static str, bool) {
+        match self {
+            Operator::Simple => ("", ",", false, "", false),
+            Operator::Reserved => ("", ",", false, "", true),
+            Operator::Fragment => ("#", ",", false, "", true),
+            Operator::Label => (".", ".", false, "", false),
+            Operator::Path => ("/", "/", false, "", false),
+            Operator::PathParam => (";", ";", true, "", false),
+            Operator::Query => ("?", "&", true, "=", false),
+            Operator::QueryContinuation => ("&", "&", true, "=", false),
+        }
+    }
+
+    /// 查询参数形式的变量可以省略，其余变量缺失时 URI 通常不完整
+    fn optional(self) -> bool {
+        matches!(self, Operator::Query | Operator::QueryContinuation)
+    }
+}
+
+impl UriTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_string()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .ok_or_else(|| format!("unclosed '{{' at offset {}", template.len() - rest.len() + open))?;
+            parts.push(parse_expression(&rest[open + 1..open + close])?);
+            rest = &rest[open + close + 1..];
+        }
+        if rest.contains('}') {
+            return Err("unmatched '}'".to_string());
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    /// 模板中的变量名，按出现顺序去重
+    pub fn variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for var in self.var_specs() {
+            if !names.contains(&var.1.name.as_str()) {
+                names.push(&var.1.name);
+            }
+        }
+        names
+    }
+
+    /// 出现在路径等位置、缺失时无法得到有效 URI 的变量
+    pub fn required_variables(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for (op, var) in self.var_specs() {
+            if !op.optional() && !names.contains(&var.name.as_str()) {
+                names.push(&var.name);
+            }
+        }
+        names
+    }
+
+    fn var_specs(&self) -> impl Iterator<Item = (Operator, &VarSpec)> {
+        self.parts.iter().flat_map(|part| match part {
+            Part::Literal(_) => Vec::new(),
+            Part::Expression { op, vars } => vars.iter().map(|v| (*op, v)).collect(),
+        })
+    }
+
+    /// 展开模板；未给出的变量按 RFC 6570 视为未定义，带 `*` 的变量把值按逗号拆成列表
+    pub fn expand(&self, values: &BTreeMap<String, String>) -> String {
+        let mut uri = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => uri.push_str(text),
+                Part::Expression { op, vars } => uri.push_str(&expand_expression(*op, vars, values)),
+            }
+        }
+        uri
+    }
+}
+
+fn parse_expression(body: &str) -> Result<Part, String> {
+    let mut chars = body.chars();
+    let op = match chars.next().and_then(Operator::from_char) {
+        Some(op) => op,
+        None => {
+            chars = body.chars();
+            Operator::Simple
+        }
+    };
+    let vars = chars
+        .as_str()
+        .split(',')
+        .map(parse_varspec)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{} in '{{{}}}'", e, body))?;
+    Ok(Part::Expression { op, vars })
+}
+
+fn parse_varspec(spec: &str) -> Result<VarSpec, String> {
+    let (name, explode, prefix) = if let Some(name) = spec.strip_suffix('*') {
+        (name, true, None)
+    } else if let Some((name, length)) = spec.split_once(':') {
+        let length = length
+            .parse::<usize>()
+            .map_err(|_| format!("invalid prefix length '{}'", length))?;
+        (name, false, Some(length))
+    } else {
+        (spec, false, None)
+    };
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '%'));
+    if !valid {
+        return Err(format!("invalid variable name '{}'", name));
+    }
+    Ok(VarSpec { name: name.to_string(), explode, prefix })
+}
+
+fn expand_expression(op: Operator, vars: &[VarSpec], values: &BTreeMap<String, String>) -> String {
+    let (first, sep, named, if_empty, allow_reserved) = op.rules();
+    let encode = |s: &str| encode(s, allow_reserved);
+
+    let mut expanded = Vec::new();
+    for var in vars {
+        let Some(value) = values.get(&var.name) else {
+            continue;
+        };
+        if var.explode {
+            // 空列表视为未定义
+            if value.is_empty() {
+                continue;
+            }
+            let items = value.split(',').map(|item| match (named, item.is_empty()) {
+                (true, true) => format!("{}{}", var.name, if_empty),
+                (true, false) => format!("{}={}", var.name, encode(item)),
+                (false, _) => encode(item),
+            });
+            expanded.push(items.collect::<Vec<_>>().join(sep));
+        } else {
+            let value: String = match var.prefix {
+                Some(length) => value.chars().take(length).collect(),
+                None => value.clone(),
+            };
+            expanded.push(match (named, value.is_empty()) {
+                (true, true) => format!("{}{}", var.name, if_empty),
+                (true, false) => format!("{}={}", var.name, encode(&value)),
+                (false, _) => encode(&value),
+            });
+        }
+    }
+
+    if expanded.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", first, expanded.join(sep))
+    }
+}
+
+/// 非保留字符原样保留，其余按 UTF-8 百分号编码；`+` 和 `#` 展开时还保留 reserved 字符和已编码的 %XX
+fn encode(value: &str, allow_reserved: bool) -> String {
+    let bytes = value.as_bytes();
+    let mut encoded = String::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let is_pct = allow_reserved
+            && b == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit();
+        if is_pct {
+            encoded.push_str(&value[i..i + 3]);
+            i += 3;
+            continue;
+        }
+        let unreserved = b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~');
+        let reserved = b":/?#[]@!$&'()*+,;=".contains(&b);
+        if unreserved || (allow_reserved && reserved) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+        i += 1;
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6570 第 3.2 节示例中的变量
+    fn expand(template: &str) -> String {
+        let values: BTreeMap<String, String> = [
+            ("var", "value"),
+            ("hello", "Hello World!"),
+            ("path", "/foo/bar"),
+            ("list", "red,green,blue"),
+            ("empty", ""),
+            ("x", "1024"),
+            ("y", "768"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        UriTemplate::parse(template).unwrap().expand(&values)
+    }
+
+    #[test]
+    fn expands_each_operator() {
+        assert_eq!(expand("{var}"), "value");
+        assert_eq!(expand("{hello}"), "Hello%20World%21");
+        assert_eq!(expand("{+hello}"), "Hello%20World!");
+        assert_eq!(expand("{+path}/here"), "/foo/bar/here");
+        assert_eq!(expand("{#var}"), "#value");
+        assert_eq!(expand("X{.var}"), "X.value");
+        assert_eq!(expand("{/var,x}/here"), "/value/1024/here");
+        assert_eq!(expand("{;x,y,empty}"), ";x=1024;y=768;empty");
+        assert_eq!(expand("{?x,y,empty}"), "?x=1024&y=768&empty=");
+        assert_eq!(expand("?fixed=yes{&x}"), "?fixed=yes&x=1024");
+    }
+
+    #[test]
+    fn expands_prefix_and_explode() {
+        assert_eq!(expand("{var:3}"), "val");
+        assert_eq!(expand("{/list*}"), "/red/green/blue");
+        assert_eq!(expand("{?list*}"), "?list=red&list=green&list=blue");
+        assert_eq!(expand("{list}"), "red%2Cgreen%2Cblue");
+    }
+
+    #[test]
+    fn undefined_variables_are_dropped() {
+        assert_eq!(expand("file:///{undef}"), "file:///");
+        assert_eq!(expand("/search{?q,x}"), "/search?x=1024");
+    }
+
+    #[test]
+    fn keeps_percent_encoded_triplets_only_when_reserved() {
+        assert_eq!(encode("a%20b", true), "a%20b");
+        assert_eq!(encode("a%20b", false), "a%2520b");
+        assert_eq!(encode("100%", true), "100%25");
+    }
+
+    #[test]
+    fn lists_variables() {
+        let template = UriTemplate::parse("db://{schema}/{table}{?limit,schema}").unwrap();
+        assert_eq!(template.variables(), ["schema", "table", "limit"]);
+        assert_eq!(template.required_variables(), ["schema", "table"]);
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(UriTemplate::parse("a/{b").is_err());
+        assert!(UriTemplate::parse("a/b}").is_err());
+        assert!(UriTemplate::parse("{}").is_err());
+        assert!(UriTemplate::parse("{var:x}").is_err());
+        assert!(UriTemplate::parse("{bad-name}").is_err());
+    }
+}
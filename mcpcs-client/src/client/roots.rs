@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::Root;
+use serde::Serialize;
+
+use crate::config::{interpolate, McpConfig, RootConfig};
+use crate::redact::redact;
+use super::output::Render;
+use super::ClientManager;
+
+/// 当前生效的根目录：全局列表对所有服务器可见，按服务器配置的列表追加在后面
+#[derive(Debug, Default)]
+pub struct RootSet {
+    global: Vec<Root>,
+    servers: HashMap<String, Vec<Root>>,
+    /// 运行时用 /roots add 添加的根目录 (服务器, 根目录)，重新加载配置后依然保留
+    added: Vec<(Option<String>, Root)>,
+}
+
+impl RootSet {
+    pub fn for_server(&self, server: &str) -> Vec<Root> {
+        let mut roots = self.global.clone();
+        for root in self.servers.get(server).into_iter().flatten() {
+            if !roots.iter().any(|r| r.uri == root.uri) {
+                roots.push(root.clone());
+            }
+        }
+        roots
+    }
+
+    fn list_mut(&mut self, server: Option<&str>) -> &mut Vec<Root> {
+        match server {
+            Some(server) => self.servers.entry(server.to_string()).or_default(),
+            None => &mut self.global,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RootListing {
+    pub global: Vec<Root>,
+    /// 各服务器在全局列表之外追加的根目录
+    pub servers: BTreeMap<String, Vec<Root>>,
+}
+
+impl ClientManager {
+    /// 命令行 `--root` 指定的全局根目录，重新加载配置后依然保留；
+    /// 不经过配置文件的连接 (sse/http 子命令) 也以此为准
+    pub fn set_cli_roots(&mut self, roots: &[String]) -> Result<()> {
+        self.cli_roots = roots
+            .iter()
+            .map(|r| to_root(&RootConfig::Path(r.clone())))
+            .collect::<Result<_>>()?;
+        self.apply_roots_config(&McpConfig::default()).map(|_| ())
+    }
+
+    /// 由配置重建根目录列表，再补上运行时添加的；没有任何全局根目录时使用当前工作目录
+    /// 返回根目录因此变化的已连接服务器，由调用方通知
+    pub fn apply_roots_config(&self, config: &McpConfig) -> Result<Vec<String>> {
+        let mut global = self.cli_roots.clone();
+        for entry in config.roots.iter().flatten() {
+            push_unique(&mut global, to_root(entry)?);
+        }
+        if global.is_empty() {
+            global.push(to_root(&RootConfig::Path(".".to_string()))?);
+        }
+
+        let mut servers = HashMap::new();
+        for (name, server_conf) in &config.mcp_servers {
+            let mut roots = Vec::new();
+            for entry in server_conf.roots() {
+                push_unique(&mut roots, to_root(entry)?);
+            }
+            if !roots.is_empty() {
+                servers.insert(name.clone(), roots);
+            }
+        }
+
+        let mut roots = self.context.roots.write().unwrap();
+        let added = std::mem::take(&mut roots.added);
+        let mut rebuilt = RootSet { global, servers, added: Vec::new() };
+        for (server, root) in &added {
+            push_unique(rebuilt.list_mut(server.as_deref()), root.clone());
+        }
+        rebuilt.added = added;
+        let changed = self
+            .list_servers()
+            .into_iter()
+            .filter(|name| roots.for_server(name) != rebuilt.for_server(name))
+            .collect();
+        *roots = rebuilt;
+        Ok(changed)
+    }
+
+    /// 添加根目录并通知受影响的服务器；server 为 None 时为全局根目录
+    pub async fn add_root(&self, spec: &str, server: Option<&str>) -> Result<Root> {
+        let root = to_root(&RootConfig::Path(spec.to_string()))?;
+        {
+            let mut roots = self.context.roots.write().unwrap();
+            let list = roots.list_mut(server);
+            if list.iter().any(|r| r.uri == root.uri) {
+                anyhow::bail!("Root already exists: {}", root.uri);
+            }
+            list.push(root.clone());
+            roots.added.push((server.map(String::from), root.clone()));
+        }
+        self.notify_roots_changed(server).await;
+        Ok(root)
+    }
+
+    /// 按 URI、路径或名称移除根目录
+    pub async fn remove_root(&self, spec: &str, server: Option<&str>) -> Result<Root> {
+        let uri = to_root(&RootConfig::Path(spec.to_string()))?.uri;
+        let removed = {
+            let mut roots = self.context.roots.write().unwrap();
+            let list = roots.list_mut(server);
+            let index = list
+                .iter()
+                .position(|r| r.uri == uri || r.name.as_deref() == Some(spec))
+                .ok_or_else(|| anyhow::anyhow!("Root not found: {}", spec))?;
+            let removed = list.remove(index);
+            roots.added.retain(|(s, r)| !(s.as_deref() == server && r.uri == removed.uri));
+            removed
+        };
+        self.notify_roots_changed(server).await;
+        Ok(removed)
+    }
+
+    pub fn root_listing(&self) -> RootListing {
+        let roots = self.context.roots.read().unwrap();
+        RootListing {
+            global: roots.global.clone(),
+            servers: roots
+                .servers
+                .iter()
+                .filter(|(_, list)| !list.is_empty())
+                .map(|(name, list)| (name.clone(), list.clone()))
+                .collect(),
+        }
+    }
+
+    /// 发送 `notifications/roots/list_changed`；server 为 None 时通知所有服务器
+    pub(crate) async fn notify_roots_changed(&self, server: Option<&str>) {
+        for name in self.list_servers() {
+            if server.is_some_and(|s| s != name) {
+                continue;
+            }
+            let client = &self.clients[&name];
+            if let Err(e) = client.notify_roots_list_changed().await {
+                eprintln!("{} '{}': {}", "Failed to notify roots change to".red(), name, redact(&e.to_string()));
+            }
+        }
+    }
+}
+
+fn push_unique(roots: &mut Vec<Root>, root: Root) {
+    if !roots.iter().any(|r| r.uri == root.uri) {
+        roots.push(root);
+    }
+}
+
+/// 本地路径转换为 `file://` URI (相对路径以当前目录为基准)，已是 URI 的原样保留
+fn to_root(entry: &RootConfig) -> Result<Root> {
+    let (spec, name) = match entry {
+        RootConfig::Path(path) => (path.as_str(), None),
+        RootConfig::Entry { uri, name } => (uri.as_str(), name.clone()),
+    };
+    let spec = &interpolate(spec)?;
+
+    if spec.contains("://") {
+        return Ok(Root { uri: spec.to_string(), name });
+    }
+
+    let expanded = match spec.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?
+            .join(rest),
+        None => Path::new(spec).to_path_buf(),
+    };
+    let absolute = std::env::current_dir()?.join(expanded);
+    let path = absolute.canonicalize().unwrap_or(absolute);
+    let uri = url::Url::from_file_path(&path)
+        .map_err(|_| anyhow::anyhow!("Invalid root path: {}", spec))?;
+
+    Ok(Root {
+        uri: uri.to_string(),
+        name: name.or_else(|| path.file_name().map(|n| n.to_string_lossy().into_owned())),
+    })
+}
+
+fn print_roots(roots: &[Root]) {
+    for root in roots {
+        match &root.name {
+            Some(name) => println!("  {} {}", root.uri.cyan(), format!("({})", name).dimmed()),
+            None => println!("  {}", root.uri.cyan()),
+        }
+    }
+}
+
+impl Render for RootListing {
+    fn render_text(&self) -> Result<()> {
+        println!("{}", "Global roots:".bold());
+        if self.global.is_empty() {
+            println!("  {}", "(none)".dimmed());
+        }
+        print_roots(&self.global);
+        for (server, roots) in &self.servers {
+            println!("{} {}:", "Roots for".bold(), server.yellow());
+            print_roots(roots);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(roots: serde_json::Value) -> McpConfig {
+        serde_json::from_value(serde_json::json!({ "mcpServers": {}, "roots": roots })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn runtime_roots_survive_a_reload() {
+        let manager = ClientManager::new();
+        manager.apply_roots_config(&config(serde_json::json!(["file:///config"]))).unwrap();
+        manager.add_root("file:///added", None).await.unwrap();
+        manager.add_root("file:///only-a", Some("a")).await.unwrap();
+
+        manager.apply_roots_config(&config(serde_json::json!(["file:///reloaded"]))).unwrap();
+        let uris = |roots: &[Root]| roots.iter().map(|r| r.uri.clone()).collect::<Vec<_>>();
+        let listing = manager.root_listing();
+        assert_eq!(uris(&listing.global), ["file:///reloaded", "file:///added"]);
+        assert_eq!(uris(&listing.servers["a"]), ["file:///only-a"]);
+
+        // 移除后不再恢复
+        manager.remove_root("file:///added", None).await.unwrap();
+        manager.apply_roots_config(&config(serde_json::json!(["file:///reloaded"]))).unwrap();
+        assert_eq!(uris(&manager.root_listing().global), ["file:///reloaded"]);
+    }
+}
@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use rmcp::model::{Content, CreateMessageRequestParam, CreateMessageResult, Role, SamplingMessage};
+use rmcp::ErrorData as McpError;
+use serde::Deserialize;
+
+use super::content_text;
+
+/// 预设回复文件：单个对象或对象数组，按顺序取第一个匹配的条目
+///
+/// ```json
+/// [{ "match": "weather", "text": "Sunny", "model": "fixture" }, { "text": "default reply" }]
+/// ```
+pub struct Fixture {
+    path: String,
+    entries: Vec<FixtureEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FixtureEntry {
+    /// 请求中任意消息包含该子串时匹配；缺省匹配所有请求
+    #[serde(rename = "match")]
+    pattern: Option<String>,
+    text: String,
+    model: Option<String>,
+    stop_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FixtureFile {
+    One(FixtureEntry),
+    Many(Vec<FixtureEntry>),
+}
+
+impl Fixture {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sampling fixture {}", path))?;
+        let entries = match serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse sampling fixture {}", path))?
+        {
+            FixtureFile::One(entry) => vec![entry],
+            FixtureFile::Many(entries) => entries,
+        };
+        Ok(Self {
+            path: path.to_string(),
+            entries,
+        })
+    }
+
+    pub fn respond(&self, params: &CreateMessageRequestParam) -> Result<CreateMessageResult, McpError> {
+        let texts: Vec<String> = params.messages.iter().map(content_text).collect();
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| match &entry.pattern {
+                Some(pattern) => texts.iter().any(|t| t.contains(pattern.as_str())),
+                None => true,
+            })
+            .ok_or_else(|| {
+                McpError::internal_error(format!("No fixture in {} matches the request", self.path), None)
+            })?;
+
+        Ok(CreateMessageResult {
+            model: entry.model.clone().unwrap_or_else(|| "fixture".to_string()),
+            stop_reason: Some(
+                entry
+                    .stop_reason
+                    .clone()
+                    .unwrap_or_else(|| CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+            ),
+            message: SamplingMessage {
+                role: Role::Assistant,
+                content: Content::text(entry.text.clone()),
+            },
+        })
+    }
+}
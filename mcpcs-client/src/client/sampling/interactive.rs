@@ -0,0 +1,29 @@
+use colored::Colorize;
+use rmcp::model::{Content, CreateMessageRequestParam, CreateMessageResult, Role, SamplingMessage};
+use rmcp::ErrorData as McpError;
+
+use crate::console;
+use super::rejected;
+
+/// 由操作者输入回复；空行或 EOF 视为拒绝
+pub async fn respond(_params: &CreateMessageRequestParam) -> Result<CreateMessageResult, McpError> {
+    let console = console::global();
+    console.print_above(&"Type the assistant reply (empty line declines):".dimmed().to_string());
+
+    let reply = console
+        .read_line(&format!("{} ", "assistant>".magenta().bold()))
+        .await
+        .unwrap_or_default();
+    if reply.trim().is_empty() {
+        return Err(rejected("User declined the sampling request"));
+    }
+
+    Ok(CreateMessageResult {
+        model: "human".to_string(),
+        stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+        message: SamplingMessage {
+            role: Role::Assistant,
+            content: Content::text(reply),
+        },
+    })
+}
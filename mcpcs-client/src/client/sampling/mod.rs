@@ -0,0 +1,178 @@
+mod fixture;
+mod interactive;
+mod openai;
+
+use anyhow::Result;
+use colored::Colorize;
+use rmcp::model::{
+    CreateMessageRequestParam, CreateMessageResult, ErrorCode, RawContent, Role, SamplingMessage,
+};
+use rmcp::ErrorData as McpError;
+
+use crate::client::ClientManager;
+use crate::config::SamplingConfig;
+use crate::console;
+use fixture::Fixture;
+use openai::OpenAiBackend;
+
+/// 回答服务器 `sampling/createMessage` 请求的后端
+#[derive(Default)]
+pub enum SamplingResponder {
+    #[default]
+    Interactive,
+    Decline,
+    Fixture(Fixture),
+    OpenAi(OpenAiBackend),
+}
+
+impl SamplingResponder {
+    pub fn from_config(config: &SamplingConfig) -> Result<Self> {
+        Ok(match config {
+            SamplingConfig::Interactive => SamplingResponder::Interactive,
+            SamplingConfig::Decline => SamplingResponder::Decline,
+            SamplingConfig::Fixture { path } => SamplingResponder::Fixture(Fixture::load(path)?),
+            SamplingConfig::Openai { base_url, model, api_key, network } => SamplingResponder::OpenAi(
+                OpenAiBackend::new(base_url, model.clone(), api_key.clone(), network)?,
+            ),
+        })
+    }
+
+    fn mode(&self) -> &'static str {
+        match self {
+            SamplingResponder::Interactive => "interactive",
+            SamplingResponder::Decline => "decline",
+            SamplingResponder::Fixture(_) => "fixture",
+            SamplingResponder::OpenAi(_) => "openai",
+        }
+    }
+
+    /// 先展示请求内容，再交给对应后端生成回复
+    pub async fn respond(
+        &self,
+        server: &str,
+        params: CreateMessageRequestParam,
+    ) -> Result<CreateMessageResult, McpError> {
+        print_request(server, self.mode(), &params);
+
+        let result = match self {
+            SamplingResponder::Interactive => interactive::respond(&params).await,
+            SamplingResponder::Decline => Err(rejected("Sampling request declined by client")),
+            SamplingResponder::Fixture(fixture) => fixture.respond(&params),
+            SamplingResponder::OpenAi(backend) => backend.respond(&params).await,
+        };
+
+        let console = console::global();
+        match &result {
+            Ok(reply) => console.print_above(&format!(
+                "{} {} ({})",
+                "Sampling reply sent, model".green(),
+                reply.model.cyan(),
+                reply.stop_reason.as_deref().unwrap_or("no stop reason")
+            )),
+            Err(e) => console.print_above(&format!("{} {}", "Sampling request failed:".red(), e.message)),
+        }
+        result
+    }
+}
+
+impl ClientManager {
+    /// 命令行指定的采样模式，优先于配置文件
+    pub fn pin_sampling(&mut self, config: &SamplingConfig) -> Result<()> {
+        self.set_sampling(SamplingResponder::from_config(config)?);
+        self.sampling_pinned = true;
+        Ok(())
+    }
+
+    /// 应用配置文件中的采样设置，未配置时回到交互模式
+    pub fn apply_sampling_config(&self, config: Option<&SamplingConfig>) -> Result<()> {
+        if self.sampling_pinned {
+            return Ok(());
+        }
+        let responder = match config {
+            Some(config) => SamplingResponder::from_config(config)?,
+            None => SamplingResponder::default(),
+        };
+        self.set_sampling(responder);
+        Ok(())
+    }
+
+    fn set_sampling(&self, responder: SamplingResponder) {
+        *self.context.sampling.write().unwrap() = std::sync::Arc::new(responder);
+    }
+}
+
+/// 用户拒绝时按规范返回错误码 -1
+pub(crate) fn rejected(message: &str) -> McpError {
+    McpError::new(ErrorCode(-1), message.to_string(), None)
+}
+
+/// 消息内容的文本形式，非文本内容用占位符表示
+pub(crate) fn content_text(message: &SamplingMessage) -> String {
+    match &*message.content {
+        RawContent::Text(text) => text.text.clone(),
+        RawContent::Image(image) => format!("[image {}]", image.mime_type),
+        RawContent::Audio(audio) => format!("[audio {}]", audio.mime_type),
+        RawContent::Resource(_) => "[embedded resource]".to_string(),
+        RawContent::ResourceLink(link) => format!("[resource link {}]", link.uri),
+    }
+}
+
+pub(crate) fn role_str(role: &Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+    }
+}
+
+fn print_request(server: &str, mode: &str, params: &CreateMessageRequestParam) {
+    let mut lines = vec![format!(
+        "{} {} {}",
+        format!("[{}]", server).cyan(),
+        "sampling/createMessage".magenta().bold(),
+        format!("(responder: {})", mode).dimmed()
+    )];
+
+    if let Some(system_prompt) = &params.system_prompt {
+        lines.push(format!("  {} {}", "System:".bold(), system_prompt));
+    }
+    for message in &params.messages {
+        lines.push(format!(
+            "  {} {}",
+            format!("{}:", role_str(&message.role)).yellow(),
+            content_text(message)
+        ));
+    }
+
+    if let Some(prefs) = &params.model_preferences {
+        let hints: Vec<&str> = prefs
+            .hints
+            .iter()
+            .flatten()
+            .filter_map(|h| h.name.as_deref())
+            .collect();
+        if !hints.is_empty() {
+            lines.push(format!("  {} {}", "Model hints:".bold(), hints.join(", ")));
+        }
+        let priorities: Vec<String> = [
+            ("cost", prefs.cost_priority),
+            ("speed", prefs.speed_priority),
+            ("intelligence", prefs.intelligence_priority),
+        ]
+        .iter()
+        .filter_map(|(name, p)| p.map(|p| format!("{}={}", name, p)))
+        .collect();
+        if !priorities.is_empty() {
+            lines.push(format!("  {} {}", "Priorities:".bold(), priorities.join(" ")));
+        }
+    }
+
+    lines.push(format!("  {} {}", "Max tokens:".bold(), params.max_tokens));
+    if let Some(temperature) = params.temperature {
+        lines.push(format!("  {} {}", "Temperature:".bold(), temperature));
+    }
+    if let Some(stop) = &params.stop_sequences {
+        lines.push(format!("  {} {:?}", "Stop sequences:".bold(), stop));
+    }
+
+    console::global().print_above(&lines.join("\n"));
+}
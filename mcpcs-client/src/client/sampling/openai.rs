@@ -0,0 +1,123 @@
+use std::time::Duration;
+use anyhow::Result;
+use reqwest::header::HeaderMap;
+use rmcp::model::{Content, CreateMessageRequestParam, CreateMessageResult, Role, SamplingMessage};
+use rmcp::ErrorData as McpError;
+use serde_json::{json, Value};
+
+use crate::client::http::client_builder;
+use crate::config::{NetworkConfig, DEFAULT_CONNECT_TIMEOUT};
+use super::{content_text, role_str};
+
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+/// 未配置 timeout 时等待模型回复的时限；生成较长回复需要的时间比普通请求长
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// OpenAI 兼容的 `/chat/completions` 后端，也可以指向本地 stub
+pub struct OpenAiBackend {
+    http: reqwest::Client,
+    base_url: String,
+    model: Option<String>,
+    api_key: Option<String>,
+}
+
+impl OpenAiBackend {
+    pub fn new(base_url: &str, model: Option<String>, api_key: Option<String>, network: &NetworkConfig) -> Result<Self> {
+        // 后端卡住时不能让服务器的 sampling 请求一直等下去
+        let timeout = network.timeout.map(Duration::from_secs).unwrap_or(DEFAULT_TIMEOUT);
+        let http = client_builder(HeaderMap::new(), network, DEFAULT_CONNECT_TIMEOUT)?
+            .timeout(timeout)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build HTTP client: {}", e))?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            api_key: api_key.or_else(|| std::env::var("OPENAI_API_KEY").ok()),
+        })
+    }
+
+    /// 配置的模型优先，其次是服务器给出的第一个模型提示
+    fn model_for(&self, params: &CreateMessageRequestParam) -> String {
+        self.model
+            .clone()
+            .or_else(|| {
+                params
+                    .model_preferences
+                    .as_ref()
+                    .and_then(|p| p.hints.as_ref())
+                    .and_then(|hints| hints.iter().find_map(|h| h.name.clone()))
+            })
+            .unwrap_or_else(|| DEFAULT_MODEL.to_string())
+    }
+
+    pub async fn respond(&self, params: &CreateMessageRequestParam) -> Result<CreateMessageResult, McpError> {
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = &params.system_prompt {
+            messages.push(json!({ "role": "system", "content": system_prompt }));
+        }
+        for message in &params.messages {
+            messages.push(json!({ "role": role_str(&message.role), "content": content_text(message) }));
+        }
+
+        let mut body = json!({
+            "model": self.model_for(params),
+            "messages": messages,
+            "max_tokens": params.max_tokens,
+        });
+        if let Some(temperature) = params.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(stop) = &params.stop_sequences {
+            body["stop"] = json!(stop);
+        }
+
+        let mut request = self
+            .http
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await.map_err(backend_error)?;
+        let status = response.status();
+        let reply: Value = response.json().await.map_err(backend_error)?;
+        if !status.is_success() {
+            return Err(McpError::internal_error(
+                format!("Sampling backend returned {}", status),
+                Some(reply),
+            ));
+        }
+
+        let choice = &reply["choices"][0];
+        let text = choice["message"]["content"].as_str().ok_or_else(|| {
+            McpError::internal_error("Sampling backend reply has no message content", Some(reply.clone()))
+        })?;
+        let stop_reason = match choice["finish_reason"].as_str() {
+            Some("stop") => Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+            Some("length") => Some(CreateMessageResult::STOP_REASON_END_MAX_TOKEN.to_string()),
+            Some(other) => Some(other.to_string()),
+            None => None,
+        };
+
+        Ok(CreateMessageResult {
+            model: reply["model"]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| self.model_for(params)),
+            stop_reason,
+            message: SamplingMessage {
+                role: Role::Assistant,
+                content: Content::text(text),
+            },
+        })
+    }
+}
+
+fn backend_error(e: reqwest::Error) -> McpError {
+    if e.is_timeout() {
+        return McpError::internal_error("Sampling backend request timed out", None);
+    }
+    McpError::internal_error(format!("Sampling backend request failed: {}", e), None)
+}
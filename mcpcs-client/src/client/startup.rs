@@ -0,0 +1,300 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::config::{AuthConfig, McpServerConfig};
+use crate::redact::redact;
+use super::connection::connect;
+use super::handler::{HandlerContext, McpcsClientHandler};
+use super::health::ServerState;
+use super::oauth;
+use super::output::Render;
+use super::{ClientError, ClientManager, ClientService};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConnectStatus {
+    Connected,
+    Failed,
+    TimedOut,
+}
+
+impl ConnectStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectStatus::Connected => "connected",
+            ConnectStatus::Failed => "failed",
+            ConnectStatus::TimedOut => "timed out",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectReport {
+    pub server: String,
+    pub status: ConnectStatus,
+    pub elapsed_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 一批连接的结果汇总，/connect、/restart 和 /retry 之后显示
+#[derive(Debug, Serialize)]
+pub struct StartupSummary {
+    pub servers: Vec<ConnectReport>,
+}
+
+/// 启动或 /reload 时对照配置所做的变更
+#[derive(Debug, Default, Serialize)]
+pub struct ReloadSummary {
+    /// 新启动或因配置变化而重启的服务器
+    pub connected: Vec<ConnectReport>,
+    pub stopped: Vec<String>,
+    pub disabled: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
+impl ClientManager {
+    /// 同时连接所有给定的服务器，各自受 connect_timeout 限制
+    /// 需要 OAuth 授权的服务器等其余服务器都登记后再逐个在浏览器中授权，避免同时弹出多个授权页面
+    /// 失败的服务器连同配置保留下来，之后可以用 /retry 重试
+    pub async fn connect_all<'a>(
+        &mut self,
+        servers: impl IntoIterator<Item = (&'a String, &'a McpServerConfig)>,
+    ) -> StartupSummary {
+        let servers: Vec<(&String, &McpServerConfig)> = servers.into_iter().collect();
+        let context = &self.context;
+        let attempts = servers.iter().map(|(name, config)| async move {
+            let started = Instant::now();
+            let outcome = establish(name, config, context).await;
+            (started.elapsed(), outcome)
+        });
+        let outcomes = futures::future::join_all(attempts).await;
+
+        let mut reports = Vec::new();
+        let mut unauthorized = Vec::new();
+        for ((name, config), (elapsed, outcome)) in servers.into_iter().zip(outcomes) {
+            match outcome {
+                Err(e) if is_authorization_required(&e) => unauthorized.push((name, config, elapsed, e)),
+                outcome => reports.push(self.record_outcome(name, config, elapsed, outcome)),
+            }
+        }
+        for (name, config, elapsed, err) in unauthorized {
+            let started = Instant::now();
+            let outcome = authorize_and_establish(name, config, &self.context, err).await;
+            reports.push(self.record_outcome(name, config, elapsed + started.elapsed(), outcome));
+        }
+        reports.sort_by(|a, b| a.server.cmp(&b.server));
+        StartupSummary { servers: reports }
+    }
+
+    /// 登记一次连接的结果并开始监视该服务器
+    fn record_outcome(
+        &mut self,
+        name: &String,
+        config: &McpServerConfig,
+        elapsed: Duration,
+        outcome: Result<ClientService>,
+    ) -> ConnectReport {
+        self.configs.insert(name.clone(), config.clone());
+        let (status, error) = match outcome {
+            Ok(client) => {
+                self.failed.remove(name);
+                self.clients.insert(name.clone(), client.into());
+                self.health.connected(name);
+                (ConnectStatus::Connected, None)
+            }
+            Err(e) => {
+                let status = if e.is::<tokio::time::error::Elapsed>() {
+                    ConnectStatus::TimedOut
+                } else {
+                    ConnectStatus::Failed
+                };
+                let error = match status {
+                    ConnectStatus::TimedOut => format!("no response within {}s", config.connect_timeout().as_secs()),
+                    _ => redact(&format!("{:#}", e)),
+                };
+                self.failed.insert(name.clone(), error.clone());
+                // 交互模式下失败的服务器由健康检查任务在后台继续重连
+                let state = if self.health_checks { ServerState::Reconnecting } else { ServerState::Failed };
+                self.health.failed(name, state, error.clone());
+                (status, Some(error))
+            }
+        };
+        self.start_monitor(name, config.clone());
+        ConnectReport {
+            server: name.clone(),
+            status,
+            elapsed_ms: elapsed.as_millis(),
+            error,
+        }
+    }
+
+    /// 连接配置中的某个服务器，包括被禁用或用 /disconnect 断开的
+    pub async fn connect_server(&mut self, name: &str) -> Result<StartupSummary> {
+        if self.clients.contains_key(name) {
+            return Err(ClientError::Usage(format!("Server '{}' is already connected", name)).into());
+        }
+        let config = self.known_config(name)?;
+        Ok(self.connect_all([(&name.to_string(), &config)]).await)
+    }
+
+    pub async fn disconnect_server(&mut self, name: &str) -> Result<()> {
+        self.known_config(name)?;
+        self.close(name).await;
+        self.health.stopped(name, ServerState::Disconnected);
+        self.status(format!("{} {}", "Disconnected:".green(), name.cyan()));
+        Ok(())
+    }
+
+    /// 断开后按当前配置重新连接，重启次数加一
+    pub async fn restart_server(&mut self, name: &str) -> Result<StartupSummary> {
+        let config = self.known_config(name)?;
+        let restarts = self.health.restarts(name);
+        self.close(name).await;
+        let summary = self.connect_all([(&name.to_string(), &config)]).await;
+        self.health.set_restarts(name, restarts + 1);
+        Ok(summary)
+    }
+
+    fn known_config(&self, name: &str) -> Result<McpServerConfig> {
+        self.configs
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ClientError::ServerNotFound(name.to_string()).into())
+    }
+
+    /// 停止服务器的监视任务并关闭连接，丢弃它的目录和订阅；配置保留
+    pub(crate) async fn close(&mut self, name: &str) {
+        self.stop_monitor(name);
+        self.failed.remove(name);
+        self.context.catalog.remove(name);
+        self.context.subscriptions.remove_server(name);
+        if let Some(client) = self.clients.remove(name) {
+            if let Ok(service) = Arc::try_unwrap(client) {
+                let _ = service.cancel().await;
+            }
+        }
+    }
+
+    /// 重新连接失败的服务器；server 为 None 时重试全部
+    pub async fn retry_failed(&mut self, server: Option<&str>) -> Result<StartupSummary> {
+        let names: Vec<String> = match server {
+            Some(name) if self.failed.contains_key(name) => vec![name.to_string()],
+            Some(name) if self.clients.contains_key(name) => {
+                return Err(ClientError::Usage(format!("Server '{}' is already connected", name)).into());
+            }
+            Some(name) => return Err(ClientError::ServerNotFound(name.to_string()).into()),
+            None => self.failed.keys().cloned().collect(),
+        };
+        let configs: Vec<(String, McpServerConfig)> = names
+            .into_iter()
+            .filter_map(|name| self.configs.get(&name).cloned().map(|config| (name, config)))
+            .collect();
+        Ok(self.connect_all(configs.iter().map(|(name, config)| (name, config))).await)
+    }
+
+}
+
+/// 在超时内完成连接、握手和目录填充；服务器握手后不回应列表请求也算超时
+pub(crate) async fn establish(
+    name: &str,
+    config: &McpServerConfig,
+    context: &Arc<HandlerContext>,
+) -> Result<ClientService> {
+    let handler = McpcsClientHandler::new(name, context.clone());
+    let connected = async {
+        let client = connect(config, handler).await?;
+        context.catalog.fill(name, &client).await;
+        anyhow::Ok(client)
+    };
+    tokio::time::timeout(config.connect_timeout(), connected).await?
+}
+
+fn is_authorization_required(err: &anyhow::Error) -> bool {
+    matches!(err.downcast_ref::<ClientError>(), Some(ClientError::AuthorizationRequired { .. }))
+}
+
+/// 前台连接收到 401 质询后先在浏览器中完成授权，再连接一次
+/// 后台重连只调用 establish，不会弹出授权
+pub(crate) async fn authorize_and_establish(
+    name: &str,
+    config: &McpServerConfig,
+    context: &Arc<HandlerContext>,
+    err: anyhow::Error,
+) -> Result<ClientService> {
+    let Some(ClientError::AuthorizationRequired { challenge, .. }) = err.downcast_ref::<ClientError>() else {
+        return Err(err);
+    };
+    let resolved = config.resolved()?;
+    let McpServerConfig::Http { url, network, .. } = &resolved else {
+        return Err(err);
+    };
+    let options = match resolved.auth()? {
+        Some(AuthConfig::OAuth(options)) => options,
+        _ => Default::default(),
+    };
+    oauth::authorize(name, url, &options, challenge, network, resolved.connect_timeout())
+        .await
+        .with_context(|| format!("OAuth authorization of '{}' failed", name))?;
+    establish(name, config, context).await
+}
+
+impl Render for ReloadSummary {
+    fn render_text(&self) -> Result<()> {
+        if self.connected.is_empty() && self.stopped.is_empty() && self.disabled.is_empty() && self.unchanged.is_empty() {
+            println!("{}", "No servers configured.".yellow());
+            return Ok(());
+        }
+        for (label, names) in [("Stopped:", &self.stopped), ("Disabled:", &self.disabled), ("Unchanged:", &self.unchanged)] {
+            if !names.is_empty() {
+                println!("{} {}", label.dimmed(), names.join(", ").cyan());
+            }
+        }
+        if !self.connected.is_empty() {
+            print_connect_table(&self.connected);
+        }
+        Ok(())
+    }
+}
+
+impl Render for StartupSummary {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
+            println!("{}", "No servers configured.".yellow());
+            return Ok(());
+        }
+        print_connect_table(&self.servers);
+        Ok(())
+    }
+}
+
+fn print_connect_table(reports: &[ConnectReport]) {
+    let width = reports.iter().map(|r| r.server.len()).max().unwrap_or(0).max("Server".len());
+    println!("{}", format!("{:<width$}  {:<10}  {:>8}", "Server", "Status", "Time").bold());
+    for report in reports {
+        let status = format!("{:<10}", report.status.as_str());
+        let status = match report.status {
+            ConnectStatus::Connected => status.green(),
+            ConnectStatus::Failed => status.red(),
+            ConnectStatus::TimedOut => status.yellow(),
+        };
+        let elapsed = format!("{:>8}", format_elapsed(Duration::from_millis(report.elapsed_ms as u64)));
+        let server = format!("{:<width$}", report.server);
+        let line = format!("{}  {}  {}", server.cyan(), status, elapsed.dimmed());
+        match &report.error {
+            Some(error) => println!("{}  {}", line, error),
+            None => println!("{}", line),
+        }
+    }
+    let failed = reports.iter().filter(|r| r.status != ConnectStatus::Connected).count();
+    if failed > 0 {
+        println!("  {}", "Use /retry [server] to reconnect failed servers.".dimmed());
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    format!("{:.2}s", elapsed.as_secs_f64())
+}
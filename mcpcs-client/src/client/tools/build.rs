@@ -0,0 +1,28 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::client::form;
+use crate::client::ClientManager;
+
+impl ClientManager {
+    /// 根据工具的 input_schema 交互式填写参数，返回带服务器前缀的工具名和参数；取消时返回 None
+    pub async fn build_tool_arguments(&self, tool_spec: &str) -> Result<Option<(String, Value)>> {
+        let details = self.tool_details(tool_spec)?;
+        let found = details.tools.into_iter().map(|t| (t.server.clone(), t)).collect();
+        let (_, found) = self.unique_match("Tool", tool_spec, found)?;
+
+        self.status(format!(
+            "{} '{}' {} '{}'",
+            "Building arguments for".dimmed(),
+            found.tool.name.green(),
+            "on".dimmed(),
+            found.server.cyan()
+        ));
+        let schema = Value::Object((*found.tool.input_schema).clone());
+        let qualified = format!("{}/{}", found.server, found.tool.name);
+        Ok(form::build_arguments(&schema)
+            .await
+            .map(|args| (qualified, Value::Object(args))))
+    }
+}
@@ -1,91 +1,184 @@
+use std::time::Duration;
 use anyhow::Result;
 use colored::Colorize;
-use rmcp::{model::CallToolRequestParam, service::RunningService, RoleClient};
+use rmcp::model::{
+    CallToolRequest, CallToolRequestParam, CallToolResult, ClientRequest, ServerResult, Tool,
+};
+use rmcp::service::PeerRequestOptions;
+use serde::Serialize;
 
-use crate::client::ClientManager;
+use crate::client::inflight::await_cancellable;
+use crate::client::output::Render;
+use crate::client::{ClientError, ClientManager, ClientService};
 use super::parse_tool_spec;
+use super::validate::{self, SchemaIssue};
+
+#[derive(Debug, Serialize)]
+pub struct ToolCallOutput {
+    pub server: String,
+    pub tool: String,
+    pub result: CallToolResult,
+    /// structured_content 与工具声明的 output_schema 不符之处
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<SchemaIssue>,
+}
 
 impl ClientManager {
-    pub async fn call_tool(&self, tool_spec: &str, args: serde_json::Value) -> Result<()> {
+    pub async fn call_tool(&self, tool_spec: &str, args: serde_json::Value, timeout: Option<Duration>) -> Result<()> {
+        let output = self.execute_tool(tool_spec, args, timeout).await?;
+        self.emit(&output)?;
+        if output.result.is_error == Some(true) {
+            return Err(ClientError::ToolFailed(output.tool).into());
+        }
+        Ok(())
+    }
+
+    /// 调用工具并返回完整结果；工具自身报告的错误 (`is_error`) 不视为失败
+    ///
+    /// 发送前按 input_schema 校验参数，返回后按 output_schema 检查 structured_content；
+    /// 未指定 timeout 时使用服务器配置的请求超时
+    pub async fn execute_tool(
+        &self,
+        tool_spec: &str,
+        args: serde_json::Value,
+        timeout: Option<Duration>,
+    ) -> Result<ToolCallOutput> {
+        let (_, tool_name) = parse_tool_spec(tool_spec);
+        let (server_name, tool) = self.resolve_tool_for_call(tool_spec)?;
+
+        if let Some(tool) = &tool {
+            let issues = self.check_schema("input", tool, &tool.input_schema, &args);
+            if !issues.is_empty() {
+                return Err(ClientError::InvalidArguments {
+                    tool: tool_name.to_string(),
+                    issues: issues.iter().map(SchemaIssue::to_string).collect(),
+                }
+                .into());
+            }
+        }
+
         let args_obj = args.as_object().cloned().unwrap_or_default();
+        let client = &self.clients[&server_name];
+        let timeout = timeout.or_else(|| self.configs.get(&server_name).and_then(|c| c.request_timeout()));
+        let mut output = self.call_tool_on_server(&server_name, client, tool_name, args_obj, timeout).await?;
+
+        if let Some(tool) = &tool {
+            output.warnings = self.check_output(tool, &output.result);
+        }
+        Ok(output)
+    }
+
+    /// 确定调用哪个服务器上的工具；指定服务器但工具未出现在目录中时仍照常调用，只是无法校验
+    fn resolve_tool_for_call(&self, tool_spec: &str) -> Result<(String, Option<Tool>)> {
         let (specified_server, tool_name) = parse_tool_spec(tool_spec);
+        let found = self.context.catalog.find(specified_server, |c| &c.tools, |t| t.name == tool_name);
 
         if let Some(server_name) = specified_server {
-            if let Some(client) = self.clients.get(server_name) {
-                return call_tool_on_server(server_name, client, tool_name, args_obj).await;
-            } else {
-                println!("{} '{}'", "Server not found:".yellow(), server_name);
-                return Ok(());
+            if !self.clients.contains_key(server_name) {
+                return Err(ClientError::ServerNotFound(server_name.to_string()).into());
             }
+            return Ok((server_name.to_string(), found.into_iter().next().map(|(_, tool)| tool)));
         }
 
-        let mut servers_with_tool: Vec<String> = Vec::new();
-        for (server_name, client) in &self.clients {
-            if let Ok(response) = client.list_tools(Default::default()).await {
-                if response.tools.iter().any(|t| t.name == tool_name) {
-                    servers_with_tool.push(server_name.clone());
-                }
-            }
+        let (server_name, tool) = self.unique_match("Tool", tool_name, found)?;
+        Ok((server_name, Some(tool)))
+    }
+
+    fn check_output(&self, tool: &Tool, result: &CallToolResult) -> Vec<SchemaIssue> {
+        let Some(output_schema) = &tool.output_schema else {
+            return Vec::new();
+        };
+        if result.is_error == Some(true) {
+            return Vec::new();
+        }
+        match &result.structured_content {
+            Some(structured) => self.check_schema("output", tool, output_schema, structured),
+            None => vec![SchemaIssue {
+                path: String::new(),
+                message: "tool declares an output schema but returned no structured content".to_string(),
+            }],
         }
+    }
 
-        match servers_with_tool.len() {
-            0 => {
-                println!("{} '{}'", "Tool not found:".yellow(), tool_name);
-            }
-            1 => {
-                let server_name = &servers_with_tool[0];
-                let client = self.clients.get(server_name).unwrap();
-                call_tool_on_server(server_name, client, tool_name, args_obj).await?;
-            }
-            _ => {
-                println!(
-                    "{} '{}' {}: {}",
-                    "Conflict:".yellow().bold(),
-                    tool_name.green(),
-                    "exists in multiple servers".yellow(),
-                    servers_with_tool.join(", ").cyan()
-                );
-                println!(
-                    "{} /call {}/{} <args>",
-                    "Specify server:".dimmed(),
-                    "<server>".cyan(),
-                    tool_name.green()
-                );
+    /// schema 自身无法编译时只提示，不阻止调用
+    fn check_schema(
+        &self,
+        which: &str,
+        tool: &Tool,
+        schema: &serde_json::Map<String, serde_json::Value>,
+        instance: &serde_json::Value,
+    ) -> Vec<SchemaIssue> {
+        let schema = serde_json::Value::Object(schema.clone());
+        match validate::check(&schema, instance) {
+            Ok(issues) => issues,
+            Err(e) => {
+                self.status(format!(
+                    "{} {} schema of '{}' is invalid, skipping validation: {}",
+                    "Warning:".yellow(),
+                    which,
+                    tool.name,
+                    e
+                ));
+                Vec::new()
             }
         }
-        Ok(())
     }
-}
 
-async fn call_tool_on_server(
-    server_name: &str,
-    client: &RunningService<RoleClient, ()>,
-    tool_name: &str,
-    args_obj: serde_json::Map<String, serde_json::Value>,
-) -> Result<()> {
-    println!(
-        "{} '{}' {} '{}'...",
-        "Calling".dimmed(),
-        tool_name.green(),
-        "on".dimmed(),
-        server_name.cyan()
-    );
-
-    let result = client
-        .call_tool(CallToolRequestParam {
+    async fn call_tool_on_server(
+        &self,
+        server_name: &str,
+        client: &ClientService,
+        tool_name: &str,
+        args_obj: serde_json::Map<String, serde_json::Value>,
+        timeout: Option<Duration>,
+    ) -> Result<ToolCallOutput> {
+        self.status(format!(
+            "{} '{}' {} '{}'...",
+            "Calling".dimmed(),
+            tool_name.green(),
+            "on".dimmed(),
+            server_name.cyan()
+        ));
+
+        // 请求自带进度令牌，Ctrl+C 时取消该请求而不影响其它连接
+        let request = ClientRequest::CallToolRequest(CallToolRequest::new(CallToolRequestParam {
             name: tool_name.to_string().into(),
             arguments: Some(args_obj),
-        })
-        .await?;
+        }));
+        let handle = client
+            .send_cancellable_request(request, PeerRequestOptions::no_options())
+            .await?;
+        let label = format!("{}/{}", server_name, tool_name);
+        let result = match await_cancellable(&self.context.progress, server_name, label, handle, timeout).await? {
+            ServerResult::CallToolResult(result) => result,
+            _ => anyhow::bail!("Unexpected response to tools/call from '{}'", server_name),
+        };
 
-    println!("{} {}:", "Result from".bold(), server_name.cyan());
-    for content in result.content {
-        println!("{}", serde_json::to_string_pretty(&content)?);
+        Ok(ToolCallOutput {
+            server: server_name.to_string(),
+            tool: tool_name.to_string(),
+            result,
+            warnings: Vec::new(),
+        })
     }
-    if let Some(err) = result.is_error {
-        if err {
-            println!("{}", "(Tool reported an error state)".red());
+}
+
+impl Render for ToolCallOutput {
+    fn render_text(&self) -> Result<()> {
+        println!("{} {}:", "Result from".bold(), self.server.cyan());
+        for content in &self.result.content {
+            println!("{}", serde_json::to_string_pretty(content)?);
         }
+        if let Some(structured) = &self.result.structured_content {
+            println!("{}", "Structured content:".bold());
+            println!("{}", serde_json::to_string_pretty(structured)?);
+        }
+        if !self.warnings.is_empty() {
+            println!("{}", "Warning: structured content does not match the output schema:".yellow());
+            for issue in &self.warnings {
+                println!("  {}", issue.to_string().yellow());
+            }
+        }
+        Ok(())
     }
-    Ok(())
 }
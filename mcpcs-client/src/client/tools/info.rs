@@ -1,52 +1,68 @@
 use anyhow::Result;
 use colored::Colorize;
+use rmcp::model::Tool;
+use serde::Serialize;
 
-use crate::client::ClientManager;
+use crate::client::output::Render;
+use crate::client::{ClientError, ClientManager};
 use super::parse_tool_spec;
 
-impl ClientManager {
-    pub async fn tool_info(&self, tool_spec: &str) -> Result<()> {
-        let (specified_server, tool_name) = parse_tool_spec(tool_spec);
+#[derive(Debug, Serialize)]
+pub struct ToolDetails {
+    pub tools: Vec<ServerTool>,
+}
 
-        let mut found_tools: Vec<(String, rmcp::model::Tool)> = Vec::new();
+#[derive(Debug, Serialize)]
+pub struct ServerTool {
+    pub server: String,
+    pub tool: Tool,
+}
 
-        let servers_to_check: Vec<_> = if let Some(server_name) = specified_server {
-            if let Some(client) = self.clients.get(server_name) {
-                vec![(server_name.to_string(), client.clone())]
-            } else {
-                println!("{} '{}'", "Server not found:".yellow(), server_name);
-                return Ok(());
-            }
-        } else {
-            self.clients
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect()
-        };
+impl ClientManager {
+    pub fn tool_info(&self, tool_spec: &str) -> Result<()> {
+        let details = self.tool_details(tool_spec)?;
+        self.emit(&details)
+    }
 
-        for (server_name, client) in servers_to_check {
-            if let Ok(response) = client.list_tools(Default::default()).await {
-                for tool in response.tools {
-                    if tool.name == tool_name {
-                        found_tools.push((server_name.clone(), tool));
-                    }
-                }
+    /// 查找工具定义；未指定服务器时返回所有同名工具
+    pub fn tool_details(&self, tool_spec: &str) -> Result<ToolDetails> {
+        let (specified_server, tool_name) = parse_tool_spec(tool_spec);
+        if let Some(server_name) = specified_server {
+            if !self.clients.contains_key(server_name) {
+                return Err(ClientError::ServerNotFound(server_name.to_string()).into());
             }
         }
 
+        let found_tools: Vec<ServerTool> = self
+            .context
+            .catalog
+            .find(specified_server, |c| &c.tools, |t| t.name == tool_name)
+            .into_iter()
+            .map(|(server, tool)| ServerTool { server, tool })
+            .collect();
+
         if found_tools.is_empty() {
-            println!("{} '{}'", "Tool not found:".yellow(), tool_name);
-            return Ok(());
+            return Err(ClientError::NotFound {
+                kind: "Tool",
+                name: tool_name.to_string(),
+            }
+            .into());
         }
 
-        for (server_name, tool) in &found_tools {
-            print_tool_info(server_name, tool)?;
+        Ok(ToolDetails { tools: found_tools })
+    }
+}
+
+impl Render for ToolDetails {
+    fn render_text(&self) -> Result<()> {
+        for found in &self.tools {
+            print_tool_info(&found.server, &found.tool)?;
         }
         Ok(())
     }
 }
 
-fn print_tool_info(server_name: &str, tool: &rmcp::model::Tool) -> Result<()> {
+fn print_tool_info(server_name: &str, tool: &Tool) -> Result<()> {
     println!(
         "{}",
         format!("=== {} (from: {}) ===", tool.name, server_name)
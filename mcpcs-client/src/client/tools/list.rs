@@ -1,65 +1,110 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
 
+use crate::client::output::{find_conflicts, Render};
+use crate::client::paging::{slice_groups, ListView, Paged};
 use crate::client::ClientManager;
 
+#[derive(Debug, Serialize)]
+pub struct ToolListing {
+    pub servers: Vec<ServerTools>,
+    /// 工具名 -> 提供该工具的服务器 (仅包含冲突的工具)
+    pub conflicts: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerTools {
+    pub server: String,
+    pub tools: Vec<ToolSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolSummary {
+    pub name: String,
+    pub description: String,
+}
+
 impl ClientManager {
-    pub async fn list_tools(&self) -> Result<()> {
-        if self.clients.is_empty() {
-            println!("{}", "No connected servers.".yellow());
-            return Ok(());
+    pub async fn list_tools(&self, view: ListView) -> Result<()> {
+        let listing = self.tool_listing();
+        self.emit_list(listing, view).await
+    }
+
+    pub fn tool_listing(&self) -> ToolListing {
+        let mut servers = Vec::new();
+
+        for server_name in self.list_servers() {
+            let listed = self.context.catalog.get(&server_name).tools;
+            let tools = listed
+                .items
+                .iter()
+                .map(|t| ToolSummary {
+                    name: t.name.to_string(),
+                    description: t.description.clone().unwrap_or_default().to_string(),
+                })
+                .collect();
+            servers.push(ServerTools { server: server_name, tools, error: listed.error });
         }
 
-        let mut tool_to_servers: HashMap<String, Vec<String>> = HashMap::new();
-        let mut server_tools: Vec<(String, Vec<(String, String)>)> = Vec::new();
+        let conflicts = find_conflicts(
+            servers
+                .iter()
+                .flat_map(|s| s.tools.iter().map(move |t| (s.server.as_str(), t.name.as_str()))),
+        );
+        ToolListing { servers, conflicts }
+    }
+}
 
-        for (server_name, client) in &self.clients {
-            let result = client.list_tools(Default::default()).await;
-            match result {
-                Ok(response) => {
-                    let tools: Vec<(String, String)> = response
-                        .tools
-                        .iter()
-                        .map(|t| {
-                            (
-                                t.name.to_string(),
-                                t.description.clone().unwrap_or_default().to_string(),
-                            )
-                        })
-                        .collect();
+impl Paged for ToolListing {
+    const ITEMS: &'static str = "tools";
 
-                    for (tool_name, _) in &tools {
-                        tool_to_servers
-                            .entry(tool_name.clone())
-                            .or_default()
-                            .push(server_name.clone());
-                    }
-                    server_tools.push((server_name.clone(), tools));
-                }
-                Err(e) => {
-                    server_tools.push((server_name.clone(), vec![]));
-                    eprintln!(
-                        "{} {}: {}",
-                        "Error listing tools from".red(),
-                        server_name,
-                        e
-                    );
-                }
-            }
+    fn item_count(&self) -> usize {
+        self.servers.iter().map(|s| s.tools.len()).sum()
+    }
+
+    fn slice(&self, start: usize, count: usize) -> Self {
+        let servers = slice_groups(
+            &self.servers,
+            |s| &s.tools,
+            |s, tools| ServerTools { server: s.server.clone(), tools, error: s.error.clone() },
+            start,
+            count,
+        );
+        // 只保留这一页中出现的冲突
+        let mut conflicts = self.conflicts.clone();
+        conflicts.retain(|key, _| servers.iter().any(|s| s.tools.iter().any(|i| &i.name == key)));
+        Self { servers, conflicts }
+    }
+}
+
+impl Render for ToolListing {
+    fn render_text(&self) -> Result<()> {
+        if self.servers.is_empty() {
+            println!("{}", "No connected servers.".yellow());
+            return Ok(());
         }
 
-        let conflicts: HashMap<&String, &Vec<String>> = tool_to_servers
-            .iter()
-            .filter(|(_, servers)| servers.len() > 1)
-            .collect();
+        for server in &self.servers {
+            if let Some(e) = &server.error {
+                eprintln!(
+                    "{} {}: {}",
+                    "Error listing tools from".red(),
+                    server.server,
+                    e
+                );
+            }
+        }
 
-        if !conflicts.is_empty() {
+        if !self.conflicts.is_empty() {
             println!(
                 "{}",
                 "WARNING: Tool name conflicts detected:".yellow().bold()
             );
-            for (tool_name, servers) in &conflicts {
+            for (tool_name, servers) in &self.conflicts {
                 println!(
                     "  '{}' exists in: {}",
                     tool_name.red(),
@@ -73,26 +118,26 @@ impl ClientManager {
             println!();
         }
 
-        for (server_name, tools) in &server_tools {
-            println!("{} {}", "Server:".bold(), server_name.cyan().bold());
-            if tools.is_empty() {
+        for server in &self.servers {
+            println!("{} {}", "Server:".bold(), server.server.cyan().bold());
+            if server.tools.is_empty() {
                 println!("  {}", "(No tools available)".dimmed());
             } else {
-                for (tool_name, description) in tools {
-                    if conflicts.contains_key(tool_name) {
+                for tool in &server.tools {
+                    if self.conflicts.contains_key(&tool.name) {
                         println!(
                             "  {} {} {}: {}",
                             "-".dimmed(),
-                            tool_name.green(),
+                            tool.name.green(),
                             "[CONFLICT]".red().bold(),
-                            description.dimmed()
+                            tool.description.dimmed()
                         );
                     } else {
                         println!(
                             "  {} {}: {}",
                             "-".dimmed(),
-                            tool_name.green(),
-                            description.dimmed()
+                            tool.name.green(),
+                            tool.description.dimmed()
                         );
                     }
                 }
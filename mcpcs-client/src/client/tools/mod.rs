@@ -1,6 +1,8 @@
 mod list;
 mod info;
 mod call;
+mod build;
+mod validate;
 
 pub fn parse_tool_spec(tool_spec: &str) -> (Option<&str>, &str) {
     if let Some(pos) = tool_spec.find('/') {
@@ -0,0 +1,28 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// 一处不符合 schema 的位置，path 为 JSON Pointer
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() { "(root)" } else { &self.path };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// 按 schema 校验实例，返回全部问题；schema 本身无效时返回 Err (不阻止调用)
+pub fn check(schema: &Value, instance: &Value) -> Result<Vec<SchemaIssue>, String> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| e.to_string())?;
+    Ok(validator
+        .iter_errors(instance)
+        .map(|e| SchemaIssue {
+            path: e.instance_path().to_string(),
+            message: e.to_string(),
+        })
+        .collect())
+}
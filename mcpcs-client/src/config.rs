@@ -1,8 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 use anyhow::Result;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::redact::{self, redact};
+
+mod check;
+mod dialect;
+mod interpolate;
+pub use check::{check_all, describe_errors, ConfigCheck, Severity};
+pub use dialect::{export, import, strip_nulls, Dialect};
+pub use interpolate::expand as interpolate;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum McpServerConfig {
     // New format with explicit transport
@@ -12,9 +23,24 @@ pub enum McpServerConfig {
         /// Bearer token for authentication
         #[serde(skip_serializing_if = "Option::is_none")]
         auth_token: Option<String>,
+        /// Authentication scheme; `auth_token` is shorthand for `{"type": "bearer"}`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        auth: Option<AuthConfig>,
         /// Custom headers to include with requests
         #[serde(skip_serializing_if = "Option::is_none")]
         headers: Option<HashMap<String, String>>,
+        /// Roots exposed to this server in addition to the global ones
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roots: Option<Vec<RootConfig>>,
+        /// Seconds to wait for the connection and initialization handshake
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connect_timeout: Option<u64>,
+        /// Request timeout, proxy and TLS settings
+        #[serde(flatten)]
+        network: NetworkConfig,
+        /// Keep the entry in the config without starting it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        disabled: Option<bool>,
     },
     Http {
         transport: HttpTransport,
@@ -22,12 +48,26 @@ pub enum McpServerConfig {
         /// Bearer token for authentication  
         #[serde(skip_serializing_if = "Option::is_none")]
         auth_token: Option<String>,
+        /// Authentication scheme; `auth_token` is shorthand for `{"type": "bearer"}`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        auth: Option<AuthConfig>,
         /// Custom headers to include with requests
         #[serde(skip_serializing_if = "Option::is_none")]
         headers: Option<HashMap<String, String>>,
         /// Allow stateless connections (default: true)
         #[serde(skip_serializing_if = "Option::is_none")]
         stateless: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roots: Option<Vec<RootConfig>>,
+        /// Seconds to wait for the connection and initialization handshake
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connect_timeout: Option<u64>,
+        /// Request timeout, proxy and TLS settings
+        #[serde(flatten)]
+        network: NetworkConfig,
+        /// Keep the entry in the config without starting it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        disabled: Option<bool>,
     },
     ChildProcess {
         transport: ChildProcessTransport,
@@ -35,6 +75,14 @@ pub enum McpServerConfig {
         #[serde(default)]
         args: Vec<String>,
         env: Option<HashMap<String, String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roots: Option<Vec<RootConfig>>,
+        /// Seconds to wait for the connection and initialization handshake
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connect_timeout: Option<u64>,
+        /// Keep the entry in the config without starting it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        disabled: Option<bool>,
     },
     // Legacy format (backward compatibility) - no transport field
     Legacy {
@@ -42,59 +90,344 @@ pub enum McpServerConfig {
         #[serde(default)]
         args: Vec<String>,
         env: Option<HashMap<String, String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roots: Option<Vec<RootConfig>>,
+        /// Seconds to wait for the connection and initialization handshake
+        #[serde(skip_serializing_if = "Option::is_none")]
+        connect_timeout: Option<u64>,
+        /// Keep the entry in the config without starting it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        disabled: Option<bool>,
+    },
+}
+
+/// 未配置 connect_timeout 时的连接超时
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// SSE/HTTP 服务器未配置 timeout 时单个请求的超时
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// SSE/HTTP 连接的网络设置，构建 HTTP 客户端时应用
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct NetworkConfig {
+    /// 普通请求 (握手、列表、读取资源等) 的超时秒数，默认 30
+    /// 工具调用走不限时长的流式连接，以此作为默认时限 (见 request_timeout)，/call --timeout 可覆盖
+    /// 0 会让每个请求立即超时，解析时直接拒绝
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "positive_seconds")]
+    pub timeout: Option<u64>,
+    /// 代理地址，如 http://proxy.internal:3128；未设置时使用 HTTP_PROXY/HTTPS_PROXY 环境变量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// 额外信任的 CA 证书 (PEM 文件)，用于私有 CA 签发的服务器证书
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+    /// mTLS 客户端证书和私钥 (PEM 文件)，需同时设置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+    /// 不校验服务器证书，只用于测试环境
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insecure_skip_verify: Option<bool>,
+}
+
+fn positive_seconds<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    match Option::<u64>::deserialize(deserializer)? {
+        Some(0) => Err(serde::de::Error::custom("timeout must be at least 1 second")),
+        secs => Ok(secs),
+    }
+}
+
+impl McpServerConfig {
+    pub fn connect_timeout(&self) -> Duration {
+        match self {
+            McpServerConfig::Sse { connect_timeout, .. }
+            | McpServerConfig::Http { connect_timeout, .. }
+            | McpServerConfig::ChildProcess { connect_timeout, .. }
+            | McpServerConfig::Legacy { connect_timeout, .. } => connect_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+        }
+    }
+
+    /// 工具调用等待响应的默认时限，由 inflight 在超时后取消调用；本地进程没有默认时限
+    pub fn request_timeout(&self) -> Option<Duration> {
+        match self {
+            McpServerConfig::Sse { network, .. } | McpServerConfig::Http { network, .. } => Some(
+                network
+                    .timeout
+                    .map(Duration::from_secs)
+                    .unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+            ),
+            McpServerConfig::ChildProcess { .. } | McpServerConfig::Legacy { .. } => None,
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        match self {
+            McpServerConfig::Sse { disabled, .. }
+            | McpServerConfig::Http { disabled, .. }
+            | McpServerConfig::ChildProcess { disabled, .. }
+            | McpServerConfig::Legacy { disabled, .. } => disabled.unwrap_or(false),
+        }
+    }
+
+    /// 展开所有字符串字段中的 `${VAR}`、`${VAR:-default}` 和 `secret://<name>`，在连接时调用，
+    /// 保存的配置仍是原文，密钥不会出现在内存中的配置和 /reload 的比较里
+    pub fn resolved(&self) -> Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        interpolate::expand_value(&mut value, "")?;
+        redact::register_json(&value);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// SSE/HTTP 服务器的认证方式，旧的 auth_token 视为 bearer
+    pub fn auth(&self) -> Result<Option<AuthConfig>> {
+        match self {
+            McpServerConfig::Sse { auth_token, auth, .. } | McpServerConfig::Http { auth_token, auth, .. } => {
+                match (auth_token, auth) {
+                    (Some(_), Some(_)) => anyhow::bail!("use either 'auth_token' or 'auth', not both"),
+                    (Some(token), None) => Ok(Some(AuthConfig::Bearer { token: token.clone() })),
+                    (None, auth) => Ok(auth.clone()),
+                }
+            }
+            McpServerConfig::ChildProcess { .. } | McpServerConfig::Legacy { .. } => Ok(None),
+        }
+    }
+
+    pub fn roots(&self) -> &[RootConfig] {
+        match self {
+            McpServerConfig::Sse { roots, .. }
+            | McpServerConfig::Http { roots, .. }
+            | McpServerConfig::ChildProcess { roots, .. }
+            | McpServerConfig::Legacy { roots, .. } => roots.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+/// SSE/HTTP 服务器的认证方式
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum AuthConfig {
+    /// `Authorization: Bearer <token>`
+    Bearer { token: String },
+    /// `Authorization: Basic base64(username:password)`
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: String,
     },
+    /// 任意请求头，如 `X-API-Key: <value>`
+    Header { name: String, value: String },
+    /// 运行命令获取短期令牌并按 Bearer 发送；到期前或收到 401 时重新运行
+    Command {
+        command: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        /// 命令只输出令牌时多少秒后重新获取；输出 JSON 时以其中的 expires_in 为准
+        #[serde(skip_serializing_if = "Option::is_none")]
+        refresh_interval: Option<u64>,
+    },
+    /// 按 MCP 授权规范走 OAuth 2.1 授权码 + PKCE 流程；未配置认证的 HTTP 服务器收到 401 质询时也会这样做
+    #[serde(rename = "oauth")]
+    OAuth(OAuthConfig),
+}
+
+/// OAuth 客户端设置，全部可选；未给出 client_id 时向授权服务器动态注册
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct OAuthConfig {
+    /// 预先注册的客户端 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    /// 请求的权限范围；为空时使用服务器质询或元数据中的 scope
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// 回调监听的本地端口，默认随机；预先注册的客户端通常需要固定端口
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 暴露给服务器的根目录：本地路径 (相对当前目录) 或 URI，可附带显示名称
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RootConfig {
+    Path(String),
+    Entry {
+        uri: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum SseTransport {
     Sse,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum ChildProcessTransport {
     ChildProcess,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum HttpTransport {
     Http,
 }
 
+/// 服务器发起 `sampling/createMessage` 时由谁来回答
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "mode", rename_all = "kebab-case")]
+pub enum SamplingConfig {
+    /// 在 REPL 中由操作者输入回复
+    Interactive,
+    /// 一律拒绝
+    Decline,
+    /// 从 JSON 文件返回预设回复
+    Fixture { path: String },
+    /// 转发到 OpenAI 兼容的 chat completions 接口
+    Openai {
+        base_url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        /// 缺省时读取 OPENAI_API_KEY 环境变量
+        #[serde(skip_serializing_if = "Option::is_none")]
+        api_key: Option<String>,
+        /// 请求超时、代理和 TLS 设置，与 SSE/HTTP 服务器相同
+        #[serde(flatten)]
+        network: NetworkConfig,
+    },
+}
+
+impl std::str::FromStr for SamplingConfig {
+    type Err = String;
+
+    /// 命令行形式: `interactive`、`decline`、`fixture:<path>`、`openai:<base_url>`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "interactive" => Ok(SamplingConfig::Interactive),
+            _ if s == "decline" => Ok(SamplingConfig::Decline),
+            Some(("fixture", path)) if !path.is_empty() => Ok(SamplingConfig::Fixture {
+                path: path.to_string(),
+            }),
+            Some(("openai", url)) if !url.is_empty() => Ok(SamplingConfig::Openai {
+                base_url: url.to_string(),
+                model: None,
+                api_key: None,
+                network: NetworkConfig::default(),
+            }),
+            _ => Err(format!(
+                "invalid sampling mode '{}' (expected interactive, decline, fixture:<path> or openai:<base_url>)",
+                s
+            )),
+        }
+    }
+}
+
+/// 服务器发起 `elicitation/create` 时的应答策略
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "mode", rename_all = "kebab-case")]
+pub enum ElicitationConfig {
+    /// 在 REPL 中逐字段填写表单
+    Interactive,
+    /// 一律拒绝，适合脚本运行
+    Decline,
+    /// 从 JSON 文件返回预设答复
+    Fixture { path: String },
+}
+
+impl std::str::FromStr for ElicitationConfig {
+    type Err = String;
+
+    /// 命令行形式: `interactive`、`decline`、`fixture:<path>`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "interactive" => Ok(ElicitationConfig::Interactive),
+            _ if s == "decline" => Ok(ElicitationConfig::Decline),
+            Some(("fixture", path)) if !path.is_empty() => Ok(ElicitationConfig::Fixture {
+                path: path.to_string(),
+            }),
+            _ => Err(format!(
+                "invalid elicitation mode '{}' (expected interactive, decline or fixture:<path>)",
+                s
+            )),
+        }
+    }
+}
+
+pub fn config_dir() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    Ok(home.join(".mcpcsrs").join("mcps"))
+}
+
+/// ~/.mcpcsrs/mcps 下的所有 JSON 配置文件，按文件名排序，同名服务器以后加载的为准
+pub fn config_files() -> Result<Vec<PathBuf>> {
+    let config_dir = config_dir()?;
+    if !config_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(config_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct McpConfig {
     #[serde(rename = "mcpServers")]
     pub mcp_servers: HashMap<String, McpServerConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<ElicitationConfig>,
+    /// Roots exposed to every server; defaults to the current directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roots: Option<Vec<RootConfig>>,
 }
 
 impl McpConfig {
     pub fn load() -> Result<Self> {
         let mut config = McpConfig::default();
-        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-        let config_dir = home.join(".mcpcsrs").join("mcps");
-
-        if !config_dir.exists() {
-             return Ok(config);
-        }
-
-        let entries = std::fs::read_dir(config_dir)?;
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                let content = std::fs::read_to_string(&path)?;
-                // We assume the JSON file content structure matches McpConfig (has mcpServers key)
-                // or is it just the map?
-                // The prompt says "define MCP servers (one json can define multiple)".
-                // Usually `mcpServers` is the key.
-                match serde_json::from_str::<McpConfig>(&content) {
-                    Ok(partial_config) => {
-                        config.mcp_servers.extend(partial_config.mcp_servers);
+        for path in config_files()? {
+            let content = std::fs::read_to_string(&path)?;
+            // 解析出错时的提示可能带出字段值，先登记文件中以明文写出的秘密
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) {
+                redact::register_json(&value);
+            }
+            match serde_json::from_str::<McpConfig>(&content) {
+                Ok(partial_config) => {
+                    config.mcp_servers.extend(partial_config.mcp_servers);
+                    if partial_config.sampling.is_some() {
+                        config.sampling = partial_config.sampling;
+                    }
+                    if partial_config.elicitation.is_some() {
+                        config.elicitation = partial_config.elicitation;
                     }
-                    Err(e) => {
-                        eprintln!("Failed to parse {}: {}", path.display(), e);
+                    if let Some(roots) = partial_config.roots {
+                        config.roots.get_or_insert_with(Vec::new).extend(roots);
+                    }
+                }
+                Err(e) => {
+                    // untagged 枚举的报错只有 "did not match any variant"，改用逐字段检查的结果
+                    let issues = check::describe_errors(&path, &content);
+                    if issues.is_empty() {
+                        eprintln!("Failed to parse {}: {}", path.display(), redact(&e.to_string()));
+                    } else {
+                        eprintln!("Failed to parse {} (skipped):", path.display());
+                        for issue in issues {
+                            eprintln!("  {}", redact(&issue));
+                        }
+                        eprintln!("  Run `mcpcs-client config check` for all diagnostics.");
                     }
                 }
             }
@@ -0,0 +1,641 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::redact::redact;
+use super::{interpolate, AuthConfig, ElicitationConfig, McpServerConfig, SamplingConfig};
+
+const TOP_LEVEL_FIELDS: &[&str] = &["mcpServers", "sampling", "elicitation", "roots"];
+const TRANSPORTS: &[&str] = &["sse", "http", "child-process"];
+const SSE_FIELDS: &[&str] = &[
+    "transport", "url", "auth_token", "auth", "headers", "roots", "connect_timeout", "disabled", "timeout", "proxy",
+    "ca_cert", "client_cert", "client_key", "insecure_skip_verify",
+];
+const HTTP_FIELDS: &[&str] = &[
+    "transport", "url", "auth_token", "auth", "headers", "stateless", "roots", "connect_timeout", "disabled", "timeout",
+    "proxy", "ca_cert", "client_cert", "client_key", "insecure_skip_verify",
+];
+const PROCESS_FIELDS: &[&str] = &["transport", "command", "args", "env", "roots", "connect_timeout", "disabled"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 一条检查结果：哪个文件、哪个服务器、哪个字段出了什么问题
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// 从文件根开始的字段路径，如 `mcpServers.git.args[0]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigCheck {
+    pub files: Vec<String>,
+    pub servers: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ConfigCheck {
+    pub fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning).count()
+    }
+}
+
+/// 检查 ~/.mcpcsrs/mcps 下的所有配置文件，包括跨文件重复的服务器名
+pub fn check_all() -> Result<ConfigCheck> {
+    let mut check = ConfigCheck { files: Vec::new(), servers: 0, diagnostics: Vec::new() };
+    let mut defined: BTreeMap<String, String> = BTreeMap::new();
+    for path in super::config_files()? {
+        let file = path.display().to_string();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                check.diagnostics.push(Diagnostic {
+                    file: file.clone(),
+                    severity: Severity::Error,
+                    server: None,
+                    field: None,
+                    message: format!("cannot read file: {}", e),
+                });
+                check.files.push(file);
+                continue;
+            }
+        };
+        let (servers, diagnostics) = check_content(&path, &content);
+        check.diagnostics.extend(diagnostics);
+        for server in servers {
+            check.servers += 1;
+            // 后加载的文件覆盖先加载的
+            if let Some(previous) = defined.insert(server.clone(), file.clone()) {
+                check.diagnostics.push(Diagnostic {
+                    file: file.clone(),
+                    severity: Severity::Warning,
+                    server: Some(server.clone()),
+                    field: Some(format!("mcpServers.{}", server)),
+                    message: format!("server '{}' is also defined in {}; this definition is used", server, previous),
+                });
+            }
+        }
+        check.files.push(file);
+    }
+    Ok(check)
+}
+
+/// 检查单个文件的内容，返回其中定义的服务器名和发现的问题
+pub fn check_content(path: &Path, content: &str) -> (Vec<String>, Vec<Diagnostic>) {
+    let mut checker = Checker { file: path.display().to_string(), server: None, diagnostics: Vec::new() };
+    let root = match serde_json::from_str::<Value>(content) {
+        Ok(root) => root,
+        Err(e) => {
+            checker.error(None, format!("invalid JSON: {}", e));
+            return (Vec::new(), checker.diagnostics);
+        }
+    };
+    // 诊断信息生成时就会替换秘密，所以先登记文件中以明文写出的值
+    crate::redact::register_json(&root);
+    let Some(root) = root.as_object() else {
+        checker.error(None, "expected a JSON object with an \"mcpServers\" key".to_string());
+        return (Vec::new(), checker.diagnostics);
+    };
+    checker.unknown_fields(root, "", TOP_LEVEL_FIELDS);
+
+    let mut servers = Vec::new();
+    match root.get("mcpServers") {
+        None => checker.error(None, "missing field 'mcpServers'".to_string()),
+        Some(Value::Object(entries)) => {
+            for (name, entry) in entries {
+                checker.server = Some(name.clone());
+                checker.check_server(name, entry);
+                servers.push(name.clone());
+            }
+            checker.server = None;
+        }
+        Some(_) => checker.error(Some("mcpServers".to_string()), "expected an object of servers".to_string()),
+    }
+
+    if let Some(sampling) = root.get("sampling") {
+        if let Err(e) = serde_json::from_value::<SamplingConfig>(sampling.clone()) {
+            checker.error(Some("sampling".to_string()), e.to_string());
+        }
+    }
+    if let Some(elicitation) = root.get("elicitation") {
+        if let Err(e) = serde_json::from_value::<ElicitationConfig>(elicitation.clone()) {
+            checker.error(Some("elicitation".to_string()), e.to_string());
+        }
+    }
+    if let Some(roots) = root.get("roots") {
+        checker.check_roots("roots", roots);
+    }
+    (servers, checker.diagnostics)
+}
+
+struct Checker {
+    file: String,
+    server: Option<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    fn push(&mut self, severity: Severity, field: Option<String>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            file: self.file.clone(),
+            severity,
+            server: self.server.clone(),
+            field,
+            message: redact(&message),
+        });
+    }
+
+    fn error(&mut self, field: Option<String>, message: String) {
+        self.push(Severity::Error, field, message);
+    }
+
+    fn warning(&mut self, field: Option<String>, message: String) {
+        self.push(Severity::Warning, field, message);
+    }
+
+    /// 未知字段会被静默忽略，多半是拼写错误
+    fn unknown_fields(&mut self, object: &Map<String, Value>, path: &str, known: &[&str]) {
+        for key in object.keys() {
+            if known.contains(&key.as_str()) {
+                continue;
+            }
+            let message = match suggest(key, known) {
+                Some(similar) => format!("unknown field '{}' (did you mean '{}'?)", key, similar),
+                None => format!("unknown field '{}' (expected one of: {})", key, known.join(", ")),
+            };
+            self.warning(Some(join(path, key)), message);
+        }
+    }
+
+    fn check_server(&mut self, name: &str, entry: &Value) {
+        let path = format!("mcpServers.{}", name);
+        let Some(object) = entry.as_object() else {
+            self.error(Some(path), "expected an object".to_string());
+            return;
+        };
+
+        let transport = match object.get("transport") {
+            None => None,
+            Some(Value::String(t)) if TRANSPORTS.contains(&t.as_str()) => Some(t.as_str()),
+            Some(other) => {
+                let shown = other.as_str().map_or_else(|| other.to_string(), |s| format!("'{}'", s));
+                self.error(
+                    Some(join(&path, "transport")),
+                    format!("unsupported transport {} (expected one of: {})", shown, TRANSPORTS.join(", ")),
+                );
+                return;
+            }
+        };
+        let known = match transport {
+            Some("sse") => SSE_FIELDS,
+            Some("http") => HTTP_FIELDS,
+            _ => PROCESS_FIELDS,
+        };
+        self.unknown_fields(object, &path, known);
+
+        let errors_before = self.errors();
+        match transport {
+            Some("sse") | Some("http") => {
+                match object.get("url") {
+                    Some(url) => self.check_url(&join(&path, "url"), url),
+                    None => self.error(Some(path.clone()), format!("missing field 'url' for {} transport", transport.unwrap())),
+                }
+                if let Some(token) = object.get("auth_token") {
+                    self.resolve_string(&join(&path, "auth_token"), token);
+                }
+                if let Some(auth) = object.get("auth") {
+                    if object.contains_key("auth_token") {
+                        self.error(Some(join(&path, "auth")), "use either 'auth_token' or 'auth', not both".to_string());
+                    }
+                    self.check_auth(&join(&path, "auth"), auth);
+                }
+                if let Some(headers) = object.get("headers") {
+                    self.check_headers(&join(&path, "headers"), headers);
+                }
+                if let Some(stateless) = object.get("stateless") {
+                    self.expect_bool(&join(&path, "stateless"), stateless);
+                }
+                self.check_network(&path, object);
+            }
+            _ => {
+                match object.get("command") {
+                    Some(command) => self.check_command(&join(&path, "command"), command),
+                    None if object.contains_key("url") => self.error(
+                        Some(path.clone()),
+                        "has 'url' but no 'transport'; add \"transport\": \"http\" or \"sse\"".to_string(),
+                    ),
+                    None => self.error(Some(path.clone()), "missing field 'command'".to_string()),
+                }
+                if let Some(args) = object.get("args") {
+                    self.check_string_list(&join(&path, "args"), args);
+                }
+                match object.get("env") {
+                    None | Some(Value::Null) => {}
+                    Some(env) => self.check_string_map(&join(&path, "env"), env),
+                }
+            }
+        }
+        if let Some(timeout) = object.get("connect_timeout") {
+            if !timeout.is_u64() {
+                self.error(Some(join(&path, "connect_timeout")), "expected a whole number of seconds".to_string());
+            }
+        }
+        if let Some(disabled) = object.get("disabled") {
+            self.expect_bool(&join(&path, "disabled"), disabled);
+        }
+        if let Some(roots) = object.get("roots") {
+            self.check_roots(&join(&path, "roots"), roots);
+        }
+
+        // 兜底：逐字段检查没有发现问题但仍然无法解析时给出 serde 的原始信息
+        if self.errors() == errors_before {
+            if let Err(e) = serde_json::from_value::<McpServerConfig>(entry.clone()) {
+                self.error(Some(path), e.to_string());
+            }
+        }
+    }
+
+    fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+    }
+
+    fn expect_string<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a str> {
+        let s = value.as_str();
+        if s.is_none() {
+            self.error(Some(path.to_string()), format!("expected a string, got {}", kind(value)));
+        }
+        s
+    }
+
+    fn expect_bool(&mut self, path: &str, value: &Value) {
+        if !value.is_boolean() {
+            self.error(Some(path.to_string()), format!("expected true or false, got {}", kind(value)));
+        }
+    }
+
+    /// 展开 `${VAR}` 和 `secret://`；当前环境下无法展开只作为警告，运行时的环境可能不同
+    fn resolve_string(&mut self, path: &str, value: &Value) -> Option<String> {
+        let raw = self.expect_string(path, value)?;
+        match interpolate(raw) {
+            Ok(resolved) => Some(resolved),
+            Err(e) => {
+                self.warning(Some(path.to_string()), format!("cannot be resolved: {:#}", e));
+                None
+            }
+        }
+    }
+
+    fn check_url(&mut self, path: &str, value: &Value) {
+        let Some(url) = self.resolve_string(path, value) else {
+            return;
+        };
+        match url::Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            Ok(parsed) => self.error(
+                Some(path.to_string()),
+                format!("unsupported URL scheme '{}' (expected http or https)", parsed.scheme()),
+            ),
+            Err(e) => self.error(Some(path.to_string()), format!("invalid URL '{}': {}", url, e)),
+        }
+    }
+
+    fn check_headers(&mut self, path: &str, value: &Value) {
+        let Some(headers) = value.as_object() else {
+            self.error(Some(path.to_string()), format!("expected an object of header names to values, got {}", kind(value)));
+            return;
+        };
+        for (name, header_value) in headers {
+            let field = join(path, name);
+            if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                self.error(Some(field.clone()), format!("invalid header name '{}'", name));
+            }
+            if let Some(resolved) = self.resolve_string(&field, header_value) {
+                if reqwest::header::HeaderValue::from_str(&resolved).is_err() {
+                    self.error(Some(field), "invalid header value (control characters or non-ASCII text)".to_string());
+                }
+            }
+        }
+    }
+
+    fn check_network(&mut self, path: &str, object: &Map<String, Value>) {
+        if let Some(timeout) = object.get("timeout") {
+            match timeout.as_u64() {
+                Some(0) => self.error(Some(join(path, "timeout")), "must be at least 1 second".to_string()),
+                Some(_) => {}
+                None => self.error(Some(join(path, "timeout")), "expected a whole number of seconds".to_string()),
+            }
+        }
+        if let Some(proxy) = object.get("proxy") {
+            let field = join(path, "proxy");
+            if let Some(proxy) = self.resolve_string(&field, proxy) {
+                if reqwest::Proxy::all(&proxy).is_err() {
+                    self.error(Some(field), format!("invalid proxy URL '{}'", proxy));
+                }
+            }
+        }
+        for key in ["ca_cert", "client_cert", "client_key"] {
+            let Some(file) = object.get(key) else { continue };
+            let field = join(path, key);
+            if let Some(file) = self.resolve_string(&field, file) {
+                if !Path::new(&file).is_file() {
+                    self.error(Some(field), format!("file '{}' does not exist", file));
+                }
+            }
+        }
+        if object.contains_key("client_cert") != object.contains_key("client_key") {
+            self.error(Some(path.to_string()), "'client_cert' and 'client_key' must be set together".to_string());
+        }
+        if let Some(insecure) = object.get("insecure_skip_verify") {
+            self.expect_bool(&join(path, "insecure_skip_verify"), insecure);
+            if insecure.as_bool() == Some(true) {
+                self.warning(Some(join(path, "insecure_skip_verify")), "TLS certificate verification is disabled".to_string());
+            }
+        }
+    }
+
+    fn check_command(&mut self, path: &str, value: &Value) {
+        let Some(command) = self.resolve_string(path, value) else {
+            return;
+        };
+        if command.trim().is_empty() {
+            self.error(Some(path.to_string()), "command is empty".to_string());
+        } else if !command_exists(&command) {
+            let message = if command.contains(std::path::MAIN_SEPARATOR) || command.contains('/') {
+                format!("command '{}' does not exist or is not executable", command)
+            } else {
+                format!("command '{}' not found on PATH", command)
+            };
+            self.error(Some(path.to_string()), message);
+        }
+    }
+
+    fn check_auth(&mut self, path: &str, value: &Value) {
+        match serde_json::from_value::<AuthConfig>(value.clone()) {
+            Err(e) => self.error(Some(path.to_string()), e.to_string()),
+            Ok(AuthConfig::Header { name, .. }) if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() => {
+                self.error(Some(join(path, "name")), format!("invalid header name '{}'", name))
+            }
+            Ok(AuthConfig::Command { .. }) => {
+                if let Some(command) = value.get("command") {
+                    self.check_command(&join(path, "command"), command);
+                }
+            }
+            Ok(_) => {}
+        }
+        if let Some(fields) = value.as_object() {
+            let resolved = fields.iter().filter(|(key, f)| f.is_string() && !matches!(key.as_str(), "type" | "command"));
+            for (key, field) in resolved {
+                self.resolve_string(&join(path, key), field);
+            }
+        }
+    }
+
+    fn check_string_list(&mut self, path: &str, value: &Value) {
+        let Some(items) = value.as_array() else {
+            self.error(Some(path.to_string()), format!("expected an array of strings, got {}", kind(value)));
+            return;
+        };
+        for (i, item) in items.iter().enumerate() {
+            self.resolve_string(&format!("{}[{}]", path, i), item);
+        }
+    }
+
+    fn check_string_map(&mut self, path: &str, value: &Value) {
+        let Some(map) = value.as_object() else {
+            self.error(Some(path.to_string()), format!("expected an object of strings, got {}", kind(value)));
+            return;
+        };
+        for (key, item) in map {
+            self.resolve_string(&join(path, key), item);
+        }
+    }
+
+    fn check_roots(&mut self, path: &str, value: &Value) {
+        let Some(items) = value.as_array() else {
+            self.error(Some(path.to_string()), format!("expected an array of paths or {{\"uri\", \"name\"}} objects, got {}", kind(value)));
+            return;
+        };
+        for (i, item) in items.iter().enumerate() {
+            let field = format!("{}[{}]", path, i);
+            match item {
+                Value::String(_) => {
+                    self.resolve_string(&field, item);
+                }
+                Value::Object(entry) => {
+                    self.unknown_fields(entry, &field, &["uri", "name"]);
+                    match entry.get("uri") {
+                        Some(uri) => {
+                            self.resolve_string(&join(&field, "uri"), uri);
+                        }
+                        None => self.error(Some(field.clone()), "missing field 'uri'".to_string()),
+                    }
+                    if let Some(name) = entry.get("name") {
+                        self.expect_string(&join(&field, "name"), name);
+                    }
+                }
+                other => self.error(Some(field), format!("expected a path or an object, got {}", kind(other))),
+            }
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// 与已知字段的编辑距离不超过 2 时给出建议
+fn suggest<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&key.to_ascii_lowercase(), &candidate.to_ascii_lowercase()), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = (previous + usize::from(ca != *cb)).min(row[j] + 1).min(current + 1);
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// 含路径分隔符时直接检查该文件，否则在 PATH 中查找
+fn command_exists(command: &str) -> bool {
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return is_executable(path);
+    }
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    let extensions: &[&str] = if cfg!(windows) { &["", ".exe", ".cmd", ".bat"] } else { &[""] };
+    std::env::split_paths(&paths).any(|dir| {
+        extensions
+            .iter()
+            .any(|ext| is_executable(&dir.join(format!("{}{}", command, ext))))
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// 供 load 在文件解析失败时说明原因
+pub fn describe_errors(path: &Path, content: &str) -> Vec<String> {
+    check_content(path, content)
+        .1
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| match d.field {
+            Some(field) => format!("{}: {}", field, d.message),
+            None => d.message,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (字段, 级别, 信息) 三元组，便于断言
+    fn check(content: &str) -> Vec<(Option<String>, Severity, String)> {
+        check_content(Path::new("test.json"), content)
+            .1
+            .into_iter()
+            .map(|d| (d.field, d.severity, d.message))
+            .collect()
+    }
+
+    fn field(diagnostics: &[(Option<String>, Severity, String)], field: &str) -> Vec<String> {
+        diagnostics
+            .iter()
+            .filter(|d| d.0.as_deref() == Some(field))
+            .map(|d| d.2.clone())
+            .collect()
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("args", "args"), 0);
+        assert_eq!(edit_distance("arg", "args"), 1);
+        assert_eq!(edit_distance("comand", "command"), 1);
+        assert_eq!(edit_distance("hedaers", "headers"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "url"), 3);
+    }
+
+    #[test]
+    fn suggests_close_field_names_only() {
+        assert_eq!(suggest("Command", PROCESS_FIELDS), Some("command"));
+        assert_eq!(suggest("enviroment", PROCESS_FIELDS), None);
+        assert_eq!(suggest("header", HTTP_FIELDS), Some("headers"));
+    }
+
+    #[test]
+    fn valid_config_has_no_diagnostics() {
+        let content = r#"{"mcpServers": {
+            "local": {"command": "sh", "args": ["-c", "true"], "env": {"A": "1"}},
+            "remote": {"transport": "http", "url": "https://example.com/mcp", "headers": {"X-Test": "1"}, "timeout": 10}
+        }}"#;
+        assert_eq!(check(content), []);
+    }
+
+    #[test]
+    fn reports_invalid_json_and_shape() {
+        assert!(check("{").iter().any(|d| d.2.starts_with("invalid JSON")));
+        assert_eq!(check("[]")[0].2, "expected a JSON object with an \"mcpServers\" key");
+        assert_eq!(check("{}")[0].2, "missing field 'mcpServers'");
+    }
+
+    #[test]
+    fn reports_unknown_fields_with_suggestions() {
+        let diagnostics = check(r#"{"mcpServers": {"s": {"comand": "sh"}}}"#);
+        assert_eq!(field(&diagnostics, "mcpServers.s.comand"), ["unknown field 'comand' (did you mean 'command'?)"]);
+        assert_eq!(field(&diagnostics, "mcpServers.s"), ["missing field 'command'"]);
+    }
+
+    #[test]
+    fn reports_transport_problems() {
+        let diagnostics = check(r#"{"mcpServers": {
+            "a": {"transport": "websocket", "url": "ws://x"},
+            "b": {"transport": "http"},
+            "c": {"url": "https://example.com"}
+        }}"#);
+        assert!(field(&diagnostics, "mcpServers.a.transport")[0].starts_with("unsupported transport 'websocket'"));
+        assert_eq!(field(&diagnostics, "mcpServers.b"), ["missing field 'url' for http transport"]);
+        assert!(field(&diagnostics, "mcpServers.c")[0].contains("no 'transport'"));
+    }
+
+    #[test]
+    fn reports_network_problems() {
+        let diagnostics = check(r#"{"mcpServers": {"s": {
+            "transport": "sse", "url": "https://example.com/sse",
+            "timeout": 0, "client_key": "/nonexistent/key.pem", "insecure_skip_verify": true
+        }}}"#);
+        assert_eq!(field(&diagnostics, "mcpServers.s.timeout"), ["must be at least 1 second"]);
+        assert_eq!(
+            field(&diagnostics, "mcpServers.s.client_key"),
+            ["file '/nonexistent/key.pem' does not exist"]
+        );
+        assert_eq!(field(&diagnostics, "mcpServers.s"), ["'client_cert' and 'client_key' must be set together"]);
+        let insecure = diagnostics.iter().find(|d| d.0.as_deref() == Some("mcpServers.s.insecure_skip_verify")).unwrap();
+        assert_eq!(insecure.1, Severity::Warning);
+    }
+
+    #[test]
+    fn loader_rejects_a_zero_timeout_like_check() {
+        let content = r#"{"mcpServers": {"s": {"transport": "http", "url": "https://example.com/mcp", "timeout": 0}}}"#;
+        assert!(serde_json::from_str::<crate::config::McpConfig>(content).is_err());
+        assert_eq!(describe_errors(Path::new("test.json"), content), ["mcpServers.s.timeout: must be at least 1 second"]);
+        let content = content.replace("\"timeout\": 0", "\"timeout\": 5");
+        assert!(serde_json::from_str::<crate::config::McpConfig>(&content).is_ok());
+    }
+}
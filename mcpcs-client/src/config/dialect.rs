@@ -0,0 +1,524 @@
+use std::collections::{BTreeMap, HashMap};
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+use base64::Engine;
+
+use super::interpolate::closing_brace;
+use super::{AuthConfig, ChildProcessTransport, HttpTransport, McpConfig, McpServerConfig, NetworkConfig, SseTransport};
+
+/// 其他 MCP 宿主的配置格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Dialect {
+    /// 本工具的格式 (~/.mcpcsrs/mcps/*.json)
+    Mcpcs,
+    /// claude_desktop_config.json，只支持本地进程
+    ClaudeDesktop,
+    /// 项目中的 .mcp.json，用 type 区分 stdio/sse/http
+    ClaudeCode,
+    /// .cursor/mcp.json
+    Cursor,
+    /// .vscode/mcp.json，顶层键为 servers
+    Vscode,
+    /// mcp_config.json，远程服务器用 serverUrl
+    Windsurf,
+    /// cline_mcp_settings.json，远程 HTTP 的 type 为 streamableHttp
+    Cline,
+}
+
+/// 导入时认识但会丢弃的字段 (权限、自动批准等本工具没有的设置)
+const DROPPED_FIELDS: &[&str] = &[
+    "alwaysAllow", "autoApprove", "disabledTools", "cwd", "trust", "envFile", "includeTools", "excludeTools",
+];
+
+/// 导入的 timeout 不小于这个值时视为毫秒
+const MILLISECOND_TIMEOUT: u64 = 1000;
+
+/// SSE/HTTP 服务器的网络设置字段 (NetworkConfig)；本地进程没有这些设置
+const NETWORK_FIELDS: &[&str] = &["timeout", "proxy", "ca_cert", "client_cert", "client_key", "insecure_skip_verify"];
+
+/// 把各种 `mcpServers` 方言中的服务器转换为本工具的配置；notes 记录丢弃或需要手动处理的内容
+pub fn import(document: &Value, notes: &mut Vec<String>) -> Result<BTreeMap<String, McpServerConfig>> {
+    let servers = document
+        .get("mcpServers")
+        .or_else(|| document.get("servers"))
+        .or_else(|| document.get("mcp").and_then(|mcp| mcp.get("servers")))
+        .and_then(Value::as_object)
+        .ok_or_else(|| anyhow::anyhow!("no \"mcpServers\" or \"servers\" object found"))?;
+
+    let mut imported = BTreeMap::new();
+    for (name, entry) in servers {
+        let entry = entry
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("server '{}' is not an object", name))?;
+        let mut entry = Value::Object(entry.clone());
+        rewrite_strings(&mut entry, &mut |s| from_vscode_variables(name, s, notes));
+        let config = import_server(name, entry.as_object().unwrap(), notes)?;
+        imported.insert(name.clone(), config);
+    }
+    Ok(imported)
+}
+
+fn import_server(name: &str, entry: &Map<String, Value>, notes: &mut Vec<String>) -> Result<McpServerConfig> {
+    let string = |key: &str| entry.get(key).and_then(Value::as_str).map(String::from);
+    let strings = |key: &str| -> Option<HashMap<String, String>> {
+        entry.get(key).and_then(Value::as_object).map(|map| {
+            map.iter()
+                .map(|(k, v)| (k.clone(), v.as_str().map_or_else(|| v.to_string(), String::from)))
+                .collect()
+        })
+    };
+    let roots = entry.get("roots").and_then(|r| serde_json::from_value(r.clone()).ok());
+    let connect_timeout = entry.get("connect_timeout").and_then(Value::as_u64);
+    let disabled = entry.get("disabled").and_then(Value::as_bool).filter(|d| *d);
+    let mut network_fields: Map<String, Value> = entry
+        .iter()
+        .filter(|(key, _)| NETWORK_FIELDS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    let url = string("url").or_else(|| string("serverUrl")).or_else(|| string("httpUrl"));
+    let kind = string("type").or_else(|| string("transport")).map(|k| k.to_ascii_lowercase());
+    let transport = match (kind.as_deref(), &url) {
+        (Some("stdio" | "child-process" | "local"), _) => "child-process",
+        (Some("sse"), _) => "sse",
+        (Some("http" | "streamable-http" | "streamablehttp" | "streamable_http" | "remote"), _) => "http",
+        (Some(other), _) => anyhow::bail!("server '{}' has unsupported type '{}'", name, other),
+        (None, _) if entry.contains_key("command") => "child-process",
+        (None, Some(_)) if entry.contains_key("httpUrl") => "http",
+        // 没有写明类型的远程服务器按惯例以 /sse 结尾的是 SSE
+        (None, Some(url)) if url.trim_end_matches('/').ends_with("/sse") => "sse",
+        (None, Some(_)) => "http",
+        (None, None) => anyhow::bail!("server '{}' has neither 'command' nor 'url'", name),
+    };
+
+    let remote = transport != "child-process";
+    let used: &[&str] = match transport {
+        "child-process" => &["type", "transport", "command", "args", "env", "roots", "connect_timeout", "disabled"],
+        _ => &[
+            "type", "transport", "url", "serverUrl", "httpUrl", "headers", "auth_token", "auth", "stateless", "roots",
+            "connect_timeout", "disabled",
+        ],
+    };
+    let network = |key: &str| NETWORK_FIELDS.contains(&key);
+    for key in entry.keys().filter(|k| !(used.contains(&k.as_str()) || remote && network(k))) {
+        let reason = if DROPPED_FIELDS.contains(&key.as_str()) || network(key) { "not supported" } else { "unknown field" };
+        notes.push(format!("{}: dropped '{}' ({})", name, key, reason));
+    }
+
+    // 本工具的 timeout 以秒为单位，而 Claude Code、Gemini CLI 等宿主写的是毫秒 (Cline 是秒)
+    if let Some(timeout) = network_fields.get("timeout").and_then(Value::as_u64).filter(|_| remote) {
+        if timeout >= MILLISECOND_TIMEOUT {
+            let secs = timeout.div_ceil(1000);
+            notes.push(format!("{}: converted 'timeout' {} from milliseconds to {}s", name, timeout, secs));
+            network_fields.insert("timeout".into(), json!(secs));
+        } else {
+            notes.push(format!("{}: kept 'timeout' {} as seconds; check the unit used by the source host", name, timeout));
+        }
+    }
+
+    // 解析不了的 auth 丢弃时要提示，否则导入后的服务器会悄悄失去认证
+    let auth = match entry.get("auth").filter(|_| transport != "child-process") {
+        None => None,
+        Some(auth) => match serde_json::from_value::<AuthConfig>(auth.clone()) {
+            Ok(auth) => Some(auth),
+            Err(e) => {
+                notes.push(format!("{}: dropped 'auth' ({}); add the credentials by hand", name, e));
+                None
+            }
+        },
+    };
+
+    let network = if !remote {
+        NetworkConfig::default()
+    } else {
+        serde_json::from_value(Value::Object(network_fields)).unwrap_or_else(|e| {
+            notes.push(format!("{}: dropped network settings: {}", name, e));
+            NetworkConfig::default()
+        })
+    };
+
+    Ok(match transport {
+        "child-process" => McpServerConfig::ChildProcess {
+            transport: ChildProcessTransport::ChildProcess,
+            command: string("command").ok_or_else(|| anyhow::anyhow!("server '{}' has no 'command'", name))?,
+            args: entry
+                .get("args")
+                .and_then(Value::as_array)
+                .map(|args| args.iter().map(|a| a.as_str().map_or_else(|| a.to_string(), String::from)).collect())
+                .unwrap_or_default(),
+            env: strings("env").filter(|env| !env.is_empty()),
+            roots,
+            connect_timeout,
+            disabled,
+        },
+        "sse" => McpServerConfig::Sse {
+            transport: SseTransport::Sse,
+            url: url.ok_or_else(|| anyhow::anyhow!("server '{}' has no 'url'", name))?,
+            auth_token: string("auth_token"),
+            auth,
+            headers: strings("headers").filter(|h| !h.is_empty()),
+            roots,
+            connect_timeout,
+            network,
+            disabled,
+        },
+        _ => McpServerConfig::Http {
+            transport: HttpTransport::Http,
+            url: url.ok_or_else(|| anyhow::anyhow!("server '{}' has no 'url'", name))?,
+            auth_token: string("auth_token"),
+            auth,
+            headers: strings("headers").filter(|h| !h.is_empty()),
+            stateless: entry.get("stateless").and_then(Value::as_bool),
+            roots,
+            connect_timeout,
+            network,
+            disabled,
+        },
+    })
+}
+
+/// 按目标格式写出合并后的配置；目标格式表达不了的内容记在 notes 中
+pub fn export(config: &McpConfig, dialect: Dialect, notes: &mut Vec<String>) -> Result<Value> {
+    if dialect == Dialect::Mcpcs {
+        let mut document = serde_json::to_value(config)?;
+        sort_servers(&mut document, "mcpServers");
+        strip_nulls(&mut document);
+        return Ok(document);
+    }
+
+    if config.sampling.is_some() || config.elicitation.is_some() || config.roots.is_some() {
+        notes.push("sampling, elicitation and global roots settings are not exported".to_string());
+    }
+    let mut servers = Map::new();
+    let names: BTreeMap<&String, &McpServerConfig> = config.mcp_servers.iter().collect();
+    for (name, server) in names {
+        if let Some(mut entry) = export_server(name, server, dialect, notes) {
+            rewrite_strings(&mut entry, &mut |s| {
+                if s.contains("secret://") {
+                    notes.push(format!("{}: secret reference '{}' has to be replaced by hand", name, s));
+                }
+                if dialect == Dialect::Vscode {
+                    *s = to_vscode_variables(name, s, notes);
+                }
+            });
+            servers.insert(name.clone(), entry);
+        }
+    }
+    let key = if dialect == Dialect::Vscode { "servers" } else { "mcpServers" };
+    Ok(json!({ key: servers }))
+}
+
+fn export_server(name: &str, server: &McpServerConfig, dialect: Dialect, notes: &mut Vec<String>) -> Option<Value> {
+    if server.is_disabled() && !matches!(dialect, Dialect::Windsurf | Dialect::Cline) {
+        notes.push(format!("{}: skipped (disabled)", name));
+        return None;
+    }
+    if !server.roots().is_empty() {
+        notes.push(format!("{}: dropped 'roots'", name));
+    }
+    let mut entry = Map::new();
+    match server {
+        McpServerConfig::ChildProcess { command, args, env, .. } | McpServerConfig::Legacy { command, args, env, .. } => {
+            if matches!(dialect, Dialect::ClaudeCode | Dialect::Vscode | Dialect::Cline) {
+                entry.insert("type".into(), json!("stdio"));
+            }
+            entry.insert("command".into(), json!(command));
+            entry.insert("args".into(), json!(args));
+            if let Some(env) = env.as_ref().filter(|env| !env.is_empty()) {
+                entry.insert("env".into(), json!(sorted(env)));
+            }
+        }
+        McpServerConfig::Sse { url, headers, .. } | McpServerConfig::Http { url, headers, .. } => {
+            if dialect == Dialect::ClaudeDesktop {
+                notes.push(format!("{}: skipped (claude-desktop only supports local servers)", name));
+                return None;
+            }
+            let sse = matches!(server, McpServerConfig::Sse { .. });
+            let kind = match (dialect, sse) {
+                (_, true) => Some("sse"),
+                (Dialect::Cline, false) => Some("streamableHttp"),
+                (Dialect::ClaudeCode | Dialect::Vscode, false) => Some("http"),
+                _ => None,
+            };
+            if let Some(kind) = kind.filter(|_| !matches!(dialect, Dialect::Cursor | Dialect::Windsurf)) {
+                entry.insert("type".into(), json!(kind));
+            }
+            let url_key = if dialect == Dialect::Windsurf { "serverUrl" } else { "url" };
+            entry.insert(url_key.into(), json!(url));
+
+            let mut headers: BTreeMap<String, String> = headers.clone().unwrap_or_default().into_iter().collect();
+            match server.auth() {
+                Ok(Some(AuthConfig::Bearer { token })) => {
+                    headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+                }
+                Ok(Some(AuthConfig::Basic { username, password })) => {
+                    let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+                    headers.insert("Authorization".to_string(), format!("Basic {}", credentials));
+                }
+                Ok(Some(AuthConfig::Header { name: header, value })) => {
+                    headers.insert(header, value);
+                }
+                Ok(Some(AuthConfig::Command { .. })) => notes.push(format!("{}: dropped command-based 'auth'", name)),
+                Ok(Some(AuthConfig::OAuth(_))) => notes.push(format!("{}: dropped OAuth 'auth'; the server must be authorized again in the target host", name)),
+                Ok(None) => {}
+                Err(e) => notes.push(format!("{}: dropped 'auth': {}", name, e)),
+            }
+            if !headers.is_empty() {
+                entry.insert("headers".into(), json!(headers));
+            }
+            if let McpServerConfig::Http { stateless: Some(_), .. } = server {
+                notes.push(format!("{}: dropped 'stateless'", name));
+            }
+            if let McpServerConfig::Sse { network, .. } | McpServerConfig::Http { network, .. } = server {
+                if let Ok(Value::Object(fields)) = serde_json::to_value(network) {
+                    for field in fields.keys() {
+                        notes.push(format!("{}: dropped '{}'", name, field));
+                    }
+                }
+            }
+        }
+    }
+    if server.is_disabled() {
+        entry.insert("disabled".into(), json!(true));
+    }
+    Some(Value::Object(entry))
+}
+
+fn sorted(map: &HashMap<String, String>) -> BTreeMap<&String, &String> {
+    map.iter().collect()
+}
+
+fn sort_servers(document: &mut Value, key: &str) {
+    if let Some(Value::Object(servers)) = document.get_mut(key) {
+        let mut entries: Vec<(String, Value)> = std::mem::take(servers).into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        servers.extend(entries);
+    }
+}
+
+/// 去掉序列化出来的 null (如未设置的 env)，写出的文件与手写的一致
+pub fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+fn rewrite_strings(value: &mut Value, f: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter_mut().for_each(|item| rewrite_strings(item, f)),
+        Value::Object(map) => map.values_mut().for_each(|item| rewrite_strings(item, f)),
+        _ => {}
+    }
+}
+
+/// VS Code 的预定义变量，展开时会被当作同名环境变量而失败
+const VSCODE_VARIABLES: &[&str] = &[
+    "workspaceFolder", "workspaceFolderBasename", "userHome", "cwd", "file", "fileWorkspaceFolder", "relativeFile",
+    "relativeFileDirname", "fileBasename", "fileBasenameNoExtension", "fileExtname", "fileDirname",
+    "fileDirnameBasename", "lineNumber", "selectedText", "execPath", "pathSeparator", "defaultBuildTask",
+];
+
+/// VS Code 的 `${env:VAR}` 改写为 `${VAR}`；`${input:...}`、`${workspaceFolder}` 等变量无法转换，只给出提示
+fn from_vscode_variables(name: &str, s: &mut String, notes: &mut Vec<String>) {
+    let mut out = String::new();
+    let mut rest = s.as_str();
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        // 没有配对的 } 时剩下的部分原样保留
+        let Some(end) = closing_brace(&rest[2..]) else {
+            break;
+        };
+        let body = &rest[2..2 + end];
+        match body.strip_prefix("env:") {
+            Some(var) => out.push_str(&format!("${{{}}}", var)),
+            None => {
+                let vscode_only = VSCODE_VARIABLES.contains(&body) || (body.contains(':') && !body.contains(":-"));
+                if vscode_only {
+                    notes.push(format!("{}: '${{{}}}' has no equivalent; use ${{VAR}} or secret://<name>", name, body));
+                }
+                out.push_str(&rest[..end + 3]);
+            }
+        }
+        rest = &rest[end + 3..];
+    }
+    out.push_str(rest);
+    *s = out;
+}
+
+/// `${VAR}` 改写为 VS Code 的 `${env:VAR}`；VS Code 不支持默认值
+fn to_vscode_variables(name: &str, s: &str, notes: &mut Vec<String>) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        // 没有配对的 } 时剩下的部分原样保留
+        let Some(end) = closing_brace(&rest[2..]) else {
+            break;
+        };
+        let body = &rest[2..2 + end];
+        let (var, default) = match body.split_once(":-") {
+            Some((var, default)) => (var, Some(default)),
+            None => (body, None),
+        };
+        if var.is_empty() || !var.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            out.push_str(&rest[..end + 3]);
+        } else {
+            if default.is_some() {
+                notes.push(format!("{}: default value in '${{{}}}' dropped", name, body));
+            }
+            out.push_str(&format!("${{env:{}}}", var));
+        }
+        rest = &rest[end + 3..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_value(document: Value) -> (BTreeMap<String, McpServerConfig>, Vec<String>) {
+        let mut notes = Vec::new();
+        let servers = import(&document, &mut notes).unwrap();
+        (servers, notes)
+    }
+
+    fn sample() -> McpConfig {
+        serde_json::from_value(json!({ "mcpServers": {
+            "git": {
+                "transport": "child-process",
+                "command": "uvx",
+                "args": ["mcp-server-git", "--repository", "."],
+                "env": { "GIT_PAGER": "cat" }
+            },
+            "docs": {
+                "transport": "http",
+                "url": "https://example.com/mcp",
+                "headers": { "X-Api-Key": "${DOCS_KEY}" }
+            },
+            "events": { "transport": "sse", "url": "https://example.com/sse" }
+        }}))
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_every_remote_capable_dialect() {
+        let config = sample();
+        let expected: BTreeMap<String, McpServerConfig> = config.mcp_servers.clone().into_iter().collect();
+        for dialect in [Dialect::ClaudeCode, Dialect::Cursor, Dialect::Vscode, Dialect::Windsurf, Dialect::Cline] {
+            let mut notes = Vec::new();
+            let exported = export(&config, dialect, &mut notes).unwrap();
+            assert!(notes.is_empty(), "{:?}: {:?}", dialect, notes);
+            let (imported, notes) = import_value(exported);
+            assert!(notes.is_empty(), "{:?}: {:?}", dialect, notes);
+            assert_eq!(imported, expected, "{:?}", dialect);
+        }
+    }
+
+    #[test]
+    fn claude_desktop_skips_remote_servers() {
+        let mut notes = Vec::new();
+        let exported = export(&sample(), Dialect::ClaudeDesktop, &mut notes).unwrap();
+        let servers = exported["mcpServers"].as_object().unwrap();
+        assert_eq!(servers.keys().collect::<Vec<_>>(), ["git"]);
+        assert_eq!(notes.len(), 2);
+    }
+
+    #[test]
+    fn vscode_variables_are_translated_or_flagged() {
+        let (servers, notes) = import_value(json!({ "servers": { "fs": {
+            "type": "stdio",
+            "command": "npx",
+            "args": ["server-filesystem", "${workspaceFolder}"],
+            "env": { "TOKEN": "${env:FS_TOKEN}", "KEY": "${input:api-key}" }
+        }}}));
+        let McpServerConfig::ChildProcess { env, .. } = &servers["fs"] else { panic!("expected a child process") };
+        assert_eq!(env.as_ref().unwrap()["TOKEN"], "${FS_TOKEN}");
+        assert_eq!(notes.len(), 2, "{:?}", notes);
+        assert!(notes.iter().any(|n| n.contains("${workspaceFolder}")));
+        assert!(notes.iter().any(|n| n.contains("${input:api-key}")));
+    }
+
+    #[test]
+    fn unparsable_auth_is_reported() {
+        let (servers, notes) = import_value(json!({ "mcpServers": { "api": {
+            "url": "https://example.com/mcp",
+            "auth": { "type": "bearer", "tokn": "x" }
+        }}}));
+        assert!(matches!(&servers["api"], McpServerConfig::Http { auth: None, .. }));
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].starts_with("api: dropped 'auth'"), "{}", notes[0]);
+    }
+
+    #[test]
+    fn infers_transport_from_the_entry() {
+        let (servers, notes) = import_value(json!({ "mcpServers": {
+            "a": { "serverUrl": "https://example.com/sse" },
+            "b": { "httpUrl": "https://example.com/sse" },
+            "c": { "type": "streamableHttp", "url": "https://example.com/mcp", "alwaysAllow": ["x"] }
+        }}));
+        assert!(matches!(servers["a"], McpServerConfig::Sse { .. }));
+        assert!(matches!(servers["b"], McpServerConfig::Http { .. }));
+        assert!(matches!(servers["c"], McpServerConfig::Http { .. }));
+        assert_eq!(notes, ["c: dropped 'alwaysAllow' (not supported)"]);
+    }
+
+    #[test]
+    fn imported_timeouts_are_converted_to_seconds() {
+        let (servers, notes) = import_value(json!({ "mcpServers": {
+            "millis": { "type": "http", "url": "https://example.com/mcp", "timeout": 60000 },
+            "secs": { "type": "streamableHttp", "url": "https://example.com/mcp", "timeout": 90 },
+            "local": { "command": "uvx", "timeout": 30000 }
+        }}));
+        let timeout = |name: &str| match &servers[name] {
+            McpServerConfig::Http { network, .. } => network.timeout,
+            _ => panic!("expected an HTTP server"),
+        };
+        assert_eq!(timeout("millis"), Some(60));
+        assert_eq!(timeout("secs"), Some(90));
+        assert_eq!(
+            notes,
+            [
+                "millis: converted 'timeout' 60000 from milliseconds to 60s",
+                "secs: kept 'timeout' 90 as seconds; check the unit used by the source host",
+                "local: dropped 'timeout' (not supported)",
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_variables_are_kept_verbatim() {
+        let mut notes = Vec::new();
+        let mut imported = "pa${ss".to_string();
+        from_vscode_variables("s", &mut imported, &mut notes);
+        assert_eq!(imported, "pa${ss");
+        assert_eq!(to_vscode_variables("s", "pa${ss", &mut notes), "pa${ss");
+        let mut imported = "${env:A} and ${B".to_string();
+        from_vscode_variables("s", &mut imported, &mut notes);
+        assert_eq!(imported, "${A} and ${B");
+        assert_eq!(to_vscode_variables("s", "${A} and ${B", &mut notes), "${env:A} and ${B");
+        assert!(notes.is_empty(), "{:?}", notes);
+    }
+
+    #[test]
+    fn nested_defaults_match_the_outer_brace() {
+        let mut notes = Vec::new();
+        let mut imported = "x ${A:-${B}} y".to_string();
+        from_vscode_variables("s", &mut imported, &mut notes);
+        assert_eq!(imported, "x ${A:-${B}} y");
+        assert!(notes.is_empty(), "{:?}", notes);
+        assert_eq!(to_vscode_variables("s", "x ${A:-${B}} y", &mut notes), "x ${env:A} y");
+        assert_eq!(notes, ["s: default value in '${A:-${B}}' dropped"]);
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::redact;
+
+/// 整个字符串为 `secret://<name>` 时从密钥存储读取
+const SECRET_SCHEME: &str = "secret://";
+
+/// 展开配置字符串中的 `${VAR}`、`${VAR:-default}` 和 `secret://<name>`；`$${` 表示字面的 `${`
+/// 默认值本身也会展开，可以嵌套引用其他变量
+pub fn expand(value: &str) -> Result<String> {
+    if let Some(name) = value.strip_prefix(SECRET_SCHEME) {
+        return read_secret(name);
+    }
+
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start..];
+        if let Some(escaped) = after.strip_prefix("$${") {
+            expanded.push_str("${");
+            rest = escaped;
+        } else if let Some(body) = after.strip_prefix("${") {
+            let end = closing_brace(body).ok_or_else(|| anyhow::anyhow!("unclosed '${{' in '{}'", value))?;
+            expanded.push_str(&lookup(&body[..end])?);
+            rest = &body[end + 1..];
+        } else {
+            expanded.push('$');
+            rest = &after[1..];
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// 与开头的 `${` 配对的 `}`；默认值中可以再嵌套 `${...}`，如 `${A:-${B}}`
+pub(super) fn closing_brace(body: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < body.len() {
+        let rest = &body[i..];
+        if rest.starts_with("$${") {
+            i += 3;
+        } else if rest.starts_with("${") {
+            depth += 1;
+            i += 2;
+        } else if rest.starts_with('}') {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+            i += 1;
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+/// `VAR` 未设置时报错；`VAR:-default` 在未设置或为空时取默认值 (与 shell 一致)
+fn lookup(expression: &str) -> Result<String> {
+    let (name, default) = match expression.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (expression, None),
+    };
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        anyhow::bail!("invalid variable reference '${{{}}}'", expression);
+    }
+    match (std::env::var(name).ok().filter(|v| !v.is_empty()), default) {
+        (Some(value), _) => Ok(value),
+        (None, Some(default)) => expand(default),
+        (None, None) => anyhow::bail!("environment variable '{}' is not set", name),
+    }
+}
+
+/// 递归展开 JSON 中的所有字符串 (包括对象的键)，出错时带上字段路径
+pub(super) fn expand_value(value: &mut Value, path: &str) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            *s = expand(s).with_context(|| format!("in '{}'", path))?;
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                expand_value(item, &format!("{}[{}]", path, i))?;
+            }
+        }
+        Value::Object(map) => {
+            let entries = std::mem::take(map);
+            for (key, mut item) in entries {
+                let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                expand_value(&mut item, &field)?;
+                let key = expand(&key).with_context(|| format!("in the key of '{}'", field))?;
+                map.insert(key, item);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn secrets_dir() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+    Ok(home.join(".mcpcsrs"))
+}
+
+/// 先查 ~/.mcpcsrs/secrets.json (名称到值的对象)，再查 ~/.mcpcsrs/secrets/<name> 文件
+fn read_secret(name: &str) -> Result<String> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !name.starts_with('.');
+    if !valid {
+        anyhow::bail!("invalid secret name '{}'", name);
+    }
+    let dir = secrets_dir()?;
+
+    let store = dir.join("secrets.json");
+    if store.exists() {
+        check_private(&store)?;
+        let content = std::fs::read_to_string(&store)
+            .with_context(|| format!("Failed to read {}", store.display()))?;
+        let secrets: HashMap<String, String> = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {} (expected an object of strings)", store.display()))?;
+        if let Some(secret) = secrets.get(name) {
+            redact::register(secret);
+            return Ok(secret.clone());
+        }
+    }
+
+    let file = dir.join("secrets").join(name);
+    if file.exists() {
+        check_private(&file)?;
+        let secret = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let secret = secret.trim_end_matches(['\r', '\n']);
+        redact::register(secret);
+        return Ok(secret.to_string());
+    }
+
+    anyhow::bail!(
+        "secret '{}' not found in {} or {}",
+        name,
+        store.display(),
+        dir.join("secrets").display()
+    )
+}
+
+/// 密钥文件不能让同组或其他用户读写
+#[cfg(unix)]
+fn check_private(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        anyhow::bail!(
+            "refusing to read {}: permissions {:o} allow access by other users (run chmod 600 {})",
+            path.display(),
+            mode & 0o777,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_private(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 各测试使用不同的变量名，并行运行时互不影响
+    #[test]
+    fn plain_text_is_unchanged() {
+        assert_eq!(expand("no variables here").unwrap(), "no variables here");
+        assert_eq!(expand("cost: $5 and $").unwrap(), "cost: $5 and $");
+    }
+
+    #[test]
+    fn expands_variables() {
+        std::env::set_var("MCPCS_TEST_HOST", "example.com");
+        assert_eq!(expand("https://${MCPCS_TEST_HOST}/mcp").unwrap(), "https://example.com/mcp");
+        assert_eq!(expand("${MCPCS_TEST_HOST}${MCPCS_TEST_HOST}").unwrap(), "example.comexample.com");
+    }
+
+    #[test]
+    fn missing_variable_is_an_error() {
+        let e = expand("${MCPCS_TEST_MISSING}").unwrap_err();
+        assert!(e.to_string().contains("'MCPCS_TEST_MISSING' is not set"));
+    }
+
+    #[test]
+    fn default_applies_when_unset_or_empty() {
+        std::env::set_var("MCPCS_TEST_EMPTY", "");
+        std::env::set_var("MCPCS_TEST_SET", "real");
+        assert_eq!(expand("${MCPCS_TEST_UNSET:-fallback}").unwrap(), "fallback");
+        assert_eq!(expand("${MCPCS_TEST_EMPTY:-fallback}").unwrap(), "fallback");
+        assert_eq!(expand("${MCPCS_TEST_SET:-fallback}").unwrap(), "real");
+        assert_eq!(expand("${MCPCS_TEST_UNSET:-}").unwrap(), "");
+        assert_eq!(expand("${MCPCS_TEST_UNSET:-a:b/c}").unwrap(), "a:b/c");
+    }
+
+    #[test]
+    fn defaults_can_nest() {
+        std::env::set_var("MCPCS_TEST_INNER", "inner");
+        assert_eq!(expand("${MCPCS_TEST_OUTER:-${MCPCS_TEST_INNER}}").unwrap(), "inner");
+        assert_eq!(expand("${MCPCS_TEST_OUTER:-${MCPCS_TEST_NONE:-deep}}/x").unwrap(), "deep/x");
+        assert_eq!(expand("${MCPCS_TEST_OUTER:-pre-${MCPCS_TEST_INNER}-post}").unwrap(), "pre-inner-post");
+    }
+
+    #[test]
+    fn escaped_reference_is_literal() {
+        assert_eq!(expand("$${NOT_A_VAR}").unwrap(), "${NOT_A_VAR}");
+    }
+
+    #[test]
+    fn rejects_malformed_references() {
+        assert!(expand("${UNCLOSED").unwrap_err().to_string().contains("unclosed"));
+        assert!(expand("${A:-${B}").unwrap_err().to_string().contains("unclosed"));
+        assert!(expand("${}").unwrap_err().to_string().contains("invalid variable reference"));
+        assert!(expand("${BAD-NAME}").unwrap_err().to_string().contains("invalid variable reference"));
+    }
+
+    #[test]
+    fn rejects_invalid_secret_names() {
+        assert!(expand("secret://../etc/passwd").is_err());
+        assert!(expand("secret://.hidden").is_err());
+        assert!(expand("secret://").is_err());
+    }
+
+    #[test]
+    fn expand_value_reports_field_path() {
+        let mut value = serde_json::json!({ "server": { "args": ["ok", "${MCPCS_TEST_ABSENT}"] } });
+        let e = expand_value(&mut value, "").unwrap_err();
+        assert_eq!(e.to_string(), "in 'server.args[1]'");
+    }
+}
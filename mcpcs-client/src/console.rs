@@ -0,0 +1,305 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{CompletionType, Config, Editor, ExternalPrinter, Helper};
+use tokio::sync::{mpsc, oneshot};
+
+/// 标准输入只有一个：REPL 主循环、采样应答、表单等都通过 Console 排队读取一行，
+/// 服务器通知通过 `print_above` 打印在当前提示符上方
+pub struct Console {
+    requests: mpsc::UnboundedSender<Request>,
+    /// 正在等待输入时的提示符，用于通知打印后重绘
+    active_prompt: Mutex<Option<String>>,
+    /// 底部的实时状态行 (如进度)，不在等待输入时才绘制
+    status: Mutex<Option<String>>,
+    to_stderr: AtomicBool,
+    /// 启用行编辑后，编辑期间的通知交给它打印在编辑行上方
+    printer: Mutex<Option<Box<dyn ExternalPrinter + Send>>>,
+}
+
+enum Request {
+    Line(LineRequest),
+    Editor {
+        completer: Box<dyn LineCompleter>,
+        history: Option<PathBuf>,
+    },
+}
+
+struct LineRequest {
+    prompt: String,
+    /// 是否记入历史，只有 REPL 命令行才记
+    remember: bool,
+    reply: oneshot::Sender<Option<String>>,
+}
+
+/// 行编辑器按 Tab 时的补全来源，返回被替换部分的起点和候选
+pub trait LineCompleter: Send + Sync {
+    fn complete(&self, line: &str, pos: usize) -> (usize, Vec<Pair>);
+}
+
+struct EditorHelper(Box<dyn LineCompleter>);
+
+impl Completer for EditorHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &rustyline::Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        Ok(self.0.complete(line, pos))
+    }
+}
+
+impl Hinter for EditorHelper {
+    type Hint = String;
+}
+
+impl Highlighter for EditorHelper {}
+
+/// 括号没有闭合时回车只换行，方便分多行输入长 JSON
+impl Validator for EditorHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        Ok(match open_brackets(ctx.input()) {
+            true => ValidationResult::Incomplete,
+            false => ValidationResult::Valid(None),
+        })
+    }
+}
+
+impl Helper for EditorHelper {}
+
+/// 是否有未闭合的 { 或 [；只在括号内识别字符串，括号外落单的引号不影响
+fn open_brackets(input: &str) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in input.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            '"' if depth > 0 => in_string = true,
+            _ => {}
+        }
+    }
+    depth > 0 || in_string
+}
+
+/// 行编辑器及其历史文件
+struct LineEditor {
+    editor: Editor<EditorHelper, DefaultHistory>,
+    history: Option<PathBuf>,
+}
+
+/// REPL 历史最多保留的条数
+const HISTORY_SIZE: usize = 1000;
+
+static CONSOLE: OnceLock<Console> = OnceLock::new();
+
+/// 进程内唯一的 Console，首次调用时启动读取线程
+pub fn global() -> &'static Console {
+    CONSOLE.get_or_init(Console::start)
+}
+
+impl Console {
+    fn start() -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Request>();
+        std::thread::spawn(move || {
+            let mut editor: Option<LineEditor> = None;
+            while let Some(request) = rx.blocking_recv() {
+                let console = global();
+                let request = match request {
+                    Request::Line(request) => request,
+                    Request::Editor { completer, history } => {
+                        editor = console.start_editor(completer, history);
+                        continue;
+                    }
+                };
+                let result = match editor.as_mut() {
+                    Some(editor) => console.read_edited(editor, request.prompt, request.remember),
+                    None => console.read_plain(request.prompt),
+                };
+                let _ = request.reply.send(result);
+            }
+        });
+        Self {
+            requests: tx,
+            active_prompt: Mutex::new(None),
+            status: Mutex::new(None),
+            to_stderr: AtomicBool::new(false),
+            printer: Mutex::new(None),
+        }
+    }
+
+    fn read_plain(&self, prompt: String) -> Option<String> {
+        self.write_prompt(&prompt);
+        *self.active_prompt.lock().unwrap() = Some(prompt);
+
+        let mut line = String::new();
+        let result = match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+        };
+
+        *self.active_prompt.lock().unwrap() = None;
+        result
+    }
+
+    fn read_edited(&self, editor: &mut LineEditor, prompt: String, remember: bool) -> Option<String> {
+        self.write_prompt("");
+        *self.active_prompt.lock().unwrap() = Some(prompt.clone());
+        let result = match editor.editor.readline(&prompt) {
+            Ok(line) => {
+                if remember && !line.trim().is_empty() {
+                    let _ = editor.editor.add_history_entry(line.as_str());
+                    // 每条命令都立即追加到文件，异常退出也不会丢
+                    if let Some(path) = &editor.history {
+                        let _ = editor.editor.append_history(path);
+                    }
+                }
+                Some(line)
+            }
+            // Ctrl+C 只放弃当前这一行
+            Err(ReadlineError::Interrupted) => Some(String::new()),
+            Err(_) => None,
+        };
+        *self.active_prompt.lock().unwrap() = None;
+        result
+    }
+
+    /// 标准输入输出都是终端时才启用行编辑，否则保持逐行读取
+    fn start_editor(&self, completer: Box<dyn LineCompleter>, history: Option<PathBuf>) -> Option<LineEditor> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return None;
+        }
+        let config = Config::builder()
+            .completion_type(CompletionType::List)
+            .max_history_size(HISTORY_SIZE)
+            .ok()?
+            .history_ignore_dups(true)
+            .ok()?
+            .history_ignore_space(true)
+            .build();
+        let mut editor = Editor::with_config(config).ok()?;
+        editor.set_helper(Some(EditorHelper(completer)));
+        if let Some(path) = &history {
+            if path.exists() {
+                let _ = editor.load_history(path);
+            } else if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+        }
+        if let Ok(printer) = editor.create_external_printer() {
+            *self.printer.lock().unwrap() = Some(Box::new(printer));
+        }
+        Some(LineEditor { editor, history })
+    }
+
+    /// 交互模式下改用行编辑器读取输入：completer 提供 Tab 补全，命令历史保存在 history 文件
+    pub fn enable_editor(&self, completer: impl LineCompleter + 'static, history: Option<PathBuf>) {
+        let _ = self.requests.send(Request::Editor { completer: Box::new(completer), history });
+    }
+
+    /// 非交互模式下把提示和通知写到 stderr，stdout 只保留结果
+    pub fn use_stderr(&self, to_stderr: bool) {
+        self.to_stderr.store(to_stderr, Ordering::SeqCst);
+    }
+
+    /// 显示提示符并读取一行 (不含换行符)，EOF 时返回 None
+    pub async fn read_line(&self, prompt: &str) -> Option<String> {
+        self.request_line(prompt, false).await
+    }
+
+    /// 读取一条 REPL 命令，启用行编辑时记入历史
+    pub async fn read_command(&self, prompt: &str) -> Option<String> {
+        self.request_line(prompt, true).await
+    }
+
+    async fn request_line(&self, prompt: &str, remember: bool) -> Option<String> {
+        let (reply, rx) = oneshot::channel();
+        self.requests
+            .send(Request::Line(LineRequest { prompt: prompt.to_string(), remember, reply }))
+            .ok()?;
+        rx.await.ok().flatten()
+    }
+
+    /// 打印一行；若有人正在等待输入或显示着状态行，先清掉该行，打印后重绘
+    pub fn print_above(&self, text: &str) {
+        let prompt = self.active_prompt.lock().unwrap();
+        if prompt.is_some() {
+            if let Some(printer) = self.printer.lock().unwrap().as_mut() {
+                let _ = printer.print(format!("{}\n", text));
+                return;
+            }
+        }
+        let status = self.status.lock().unwrap();
+        let mut out = self.out();
+        let _ = match prompt.as_deref().or(status.as_deref()) {
+            Some(p) => write!(out, "\r\x1b[K{}\n{}", text, p),
+            None => writeln!(out, "{}", text),
+        };
+        let _ = out.flush();
+    }
+
+    /// 更新底部状态行；输出不是终端时退化为逐行打印
+    pub fn set_status(&self, line: &str) {
+        if !self.is_terminal() {
+            self.print_above(line);
+            return;
+        }
+        let prompt = self.active_prompt.lock().unwrap();
+        let mut status = self.status.lock().unwrap();
+        *status = Some(line.to_string());
+        if prompt.is_none() {
+            let mut out = self.out();
+            let _ = write!(out, "\r\x1b[K{}", line);
+            let _ = out.flush();
+        }
+    }
+
+    pub fn clear_status(&self) {
+        let prompt = self.active_prompt.lock().unwrap();
+        if self.status.lock().unwrap().take().is_some() && prompt.is_none() {
+            let mut out = self.out();
+            let _ = write!(out, "\r\x1b[K");
+            let _ = out.flush();
+        }
+    }
+
+    fn write_prompt(&self, prompt: &str) {
+        let mut out = self.out();
+        if self.status.lock().unwrap().is_some() {
+            let _ = write!(out, "\r\x1b[K");
+        }
+        let _ = write!(out, "{}", prompt);
+        let _ = out.flush();
+    }
+
+    fn out(&self) -> Box<dyn Write> {
+        if self.to_stderr.load(Ordering::SeqCst) {
+            Box::new(io::stderr().lock())
+        } else {
+            Box::new(io::stdout().lock())
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        if self.to_stderr.load(Ordering::SeqCst) {
+            io::stderr().is_terminal()
+        } else {
+            io::stdout().is_terminal()
+        }
+    }
+}
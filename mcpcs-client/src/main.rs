@@ -1,14 +1,39 @@
 mod config;
 mod client;
 mod repl;
+mod oneshot;
+mod console;
+mod redact;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+use crate::client::{ClientManager, CompletionTarget, ListView, OutputFormat};
+use crate::config::{ElicitationConfig, SamplingConfig};
+use crate::oneshot::{ConfigAction, InfoKind, ListKind, Operation};
+
 #[derive(Parser)]
 #[command(name = "mcpcs-client")]
 #[command(about = "MCP Client with multiple transport options")]
 struct Cli {
+    /// Output format for listings and results
+    #[arg(long, value_enum, global = true, default_value = "text")]
+    output: OutputFormat,
+    /// How to answer sampling requests: interactive, decline, fixture:<path> or openai:<base_url>
+    #[arg(long, global = true, value_name = "MODE")]
+    sampling: Option<SamplingConfig>,
+    /// How to answer elicitation requests: interactive, decline or fixture:<path>
+    #[arg(long, global = true, value_name = "MODE")]
+    elicitation: Option<ElicitationConfig>,
+    /// Expose a directory to servers as a root (repeatable, replaces the current-directory default)
+    #[arg(long = "root", global = true, value_name = "PATH")]
+    roots: Vec<String>,
+    /// Show long listings one page of N entries at a time when attached to a terminal
+    #[arg(long, global = true, value_name = "N")]
+    page_size: Option<usize>,
+    /// Print tokens, API keys and other secrets in diagnostics instead of masking them (for local troubleshooting)
+    #[arg(long, global = true)]
+    show_secrets: bool,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -35,80 +60,194 @@ enum Commands {
         #[arg(short, long, default_value = "http-server")]
         name: String,
     },
+    /// Call a tool and exit
+    Call {
+        /// Tool name, or <server>/<tool>
+        tool: String,
+        /// JSON arguments ("-" reads them from stdin)
+        args: Option<String>,
+        /// Give up and cancel the call after this many seconds (default: the server's timeout)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+    },
+    /// Read a resource and exit
+    Read {
+        /// Resource URI, or <server>/<uri>
+        resource: String,
+        /// Expand <resource> as a resource template with these key=value variables
+        params: Vec<String>,
+    },
+    /// List servers, tools, resources, resource templates or prompts and exit
+    List {
+        #[arg(value_enum)]
+        kind: ListKind,
+        /// Show at most N entries
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+    /// Show details of a tool, resource, resource template or prompt and exit
+    Info {
+        #[arg(value_enum)]
+        kind: InfoKind,
+        /// Name or URI, optionally prefixed with <server>/
+        name: String,
+    },
+    /// Generate a prompt and exit
+    Prompt {
+        /// Prompt name, or <server>/<prompt>
+        name: String,
+        /// Prompt arguments as key=value
+        args: Vec<String>,
+    },
+    /// Ask a server to complete a prompt argument or template variable and exit
+    Complete {
+        #[arg(value_enum)]
+        kind: CompletionTarget,
+        /// Prompt or template name, optionally prefixed with <server>/
+        name: String,
+        /// Argument or variable to complete
+        argument: String,
+        /// Partial value typed so far
+        #[arg(default_value = "")]
+        value: String,
+    },
+    /// Inspect the MCP config files
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Download a resource to a local file and exit
+    Download {
+        /// Resource URI, or <server>/<uri>
+        resource: String,
+        /// Local file path
+        path: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    // 日志经过同一个脱敏层
+    env_logger::Builder::from_default_env()
+        .format(|buf, record| {
+            use std::io::Write;
+            writeln!(buf, "[{} {}] {}", record.level(), record.target(), redact::redact(&record.args().to_string()))
+        })
+        .init();
 
     let cli = Cli::parse();
+    redact::set_show_secrets(cli.show_secrets);
+    client::listen_for_interrupts();
 
-    match cli.command.unwrap_or(Commands::Repl) {
+    let mut manager = ClientManager::new();
+    manager.set_output(cli.output);
+    manager.set_page_size(cli.page_size);
+    if let Some(sampling) = &cli.sampling {
+        if let Err(e) = manager.pin_sampling(sampling) {
+            manager.report_error("Error:", &e);
+            std::process::exit(2);
+        }
+    }
+
+    if let Some(elicitation) = &cli.elicitation {
+        if let Err(e) = manager.pin_elicitation(elicitation) {
+            manager.report_error("Error:", &e);
+            std::process::exit(2);
+        }
+    }
+    if let Err(e) = manager.set_cli_roots(&cli.roots) {
+        manager.report_error("Error:", &e);
+        std::process::exit(2);
+    }
+
+    let op = match cli.command.unwrap_or(Commands::Repl) {
         Commands::Repl => {
-            repl::run().await
+            return repl::run(manager).await;
         }
         Commands::Sse { url, name } => {
-            use crate::client::ClientManager;
             use crate::config::McpServerConfig;
             use colored::Colorize;
 
-            let mut manager = ClientManager::new();
-            
             // Create SSE config
             let sse_config = McpServerConfig::Sse { 
                 transport: crate::config::SseTransport::Sse,
                 url: url.clone(),
                 auth_token: None,  // Can be extended to accept from CLI
+                auth: None,
                 headers: None,     // Can be extended to accept from CLI
+                roots: None,
+                connect_timeout: None,
+                network: Default::default(),
+                disabled: None,
             };
             
             println!("{} {}", "Connecting to SSE server:".green(), url.cyan());
             
-            match manager.connect(&sse_config).await {
-                Ok(client) => {
-                    manager.clients.insert(name.clone(), std::sync::Arc::new(client));
+            // 与配置中的服务器走同一条连接路径：受 connect_timeout 限制，需要时在浏览器中授权
+            let summary = manager.connect_all([(&name, &sse_config)]).await;
+            match summary.servers.into_iter().next().and_then(|report| report.error) {
+                None => {
                     println!("{} {}", "Connected to SSE server:".green(), name.cyan());
                     
                     // Start interactive mode with this SSE connection
-                    repl::run_with_manager(manager).await
+                    return repl::run_with_manager(manager).await;
                 }
-                Err(e) => {
-                    eprintln!("{} {}", "Failed to connect to SSE server:".red(), e);
+                Some(error) => {
+                    eprintln!("{} {}", "Failed to connect to SSE server:".red(), error);
                     std::process::exit(1);
                 }
             }
         }
         Commands::Http { url, name } => {
-            use crate::client::ClientManager;
             use crate::config::McpServerConfig;
             use colored::Colorize;
 
-            let mut manager = ClientManager::new();
-            
             // Create HTTP config
             let http_config = McpServerConfig::Http { 
                 transport: crate::config::HttpTransport::Http,
                 url: url.clone(),
                 auth_token: None,  // Can be extended to accept from CLI
+                auth: None,
                 headers: None,     // Can be extended to accept from CLI
                 stateless: None,   // Use default (true)
+                roots: None,
+                connect_timeout: None,
+                network: Default::default(),
+                disabled: None,
             };
             
             println!("{} {}", "Connecting to HTTP server:".green(), url.cyan());
             
-            match manager.connect(&http_config).await {
-                Ok(client) => {
-                    manager.clients.insert(name.clone(), std::sync::Arc::new(client));
+            // 与配置中的服务器走同一条连接路径：受 connect_timeout 限制，需要时在浏览器中授权
+            let summary = manager.connect_all([(&name, &http_config)]).await;
+            match summary.servers.into_iter().next().and_then(|report| report.error) {
+                None => {
                     println!("{} {}", "Connected to HTTP server:".green(), name.cyan());
                     
                     // Start interactive mode with this HTTP connection
-                    repl::run_with_manager(manager).await
+                    return repl::run_with_manager(manager).await;
                 }
-                Err(e) => {
-                    eprintln!("{} {}", "Failed to connect to HTTP server:".red(), e);
+                Some(error) => {
+                    eprintln!("{} {}", "Failed to connect to HTTP server:".red(), error);
                     std::process::exit(1);
                 }
             }
         }
-    }
+        Commands::Config { action } => {
+            std::process::exit(oneshot::run_config(&manager, action));
+        }
+        Commands::Call { tool, args, timeout } => Operation::Call { tool, args, timeout },
+        Commands::Read { resource, params } => Operation::Read { resource, params },
+        Commands::List { kind, limit } => Operation::List {
+            kind,
+            view: ListView { limit, page_size: cli.page_size },
+        },
+        Commands::Info { kind, name } => Operation::Info { kind, name },
+        Commands::Prompt { name, args } => Operation::Prompt { name, args },
+        Commands::Complete { kind, name, argument, value } => Operation::Complete { kind, name, argument, value },
+        Commands::Download { resource, path } => Operation::Download { resource, path },
+    };
+
+    let code = oneshot::run(manager, op).await;
+    std::process::exit(code);
 }
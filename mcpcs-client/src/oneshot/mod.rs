@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+use anyhow::Result;
+use clap::{Subcommand, ValueEnum};
+use colored::Colorize;
+
+use crate::client::{self, ClientError, ClientManager, CompletionTarget, ListView};
+use crate::config::{Dialect, McpConfig};
+use crate::console;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ListKind {
+    Mcp,
+    Tool,
+    Resource,
+    Template,
+    Prompt,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum InfoKind {
+    Tool,
+    Resource,
+    Template,
+    Prompt,
+}
+
+/// `config` 子命令，不需要连接服务器
+#[derive(Clone, Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Validate the config files in ~/.mcpcsrs/mcps and report every problem
+    Check,
+    /// Convert another host's mcpServers file into ~/.mcpcsrs/mcps/<name>.json
+    Import {
+        /// Config file of another MCP host (Claude, Cursor, VS Code, Windsurf, Cline, ...)
+        file: String,
+        /// Config file name to write (defaults to the source file name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Only show the diff, do not write anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write the merged config in another host's format
+    Export {
+        #[arg(long, value_enum, default_value = "mcpcs")]
+        format: Dialect,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+}
+
+/// 非交互模式下执行的单个操作
+pub enum Operation {
+    Call { tool: String, args: Option<String>, timeout: Option<u64> },
+    Read { resource: String, params: Vec<String> },
+    List { kind: ListKind, view: ListView },
+    Info { kind: InfoKind, name: String },
+    Prompt { name: String, args: Vec<String> },
+    Complete { kind: CompletionTarget, name: String, argument: String, value: String },
+    Download { resource: String, path: String },
+}
+
+impl Operation {
+    /// 操作显式指定的服务器 (`server/name` 形式)，None 表示需要连接全部服务器
+    fn target_server(&self) -> Option<&str> {
+        match self {
+            Operation::Call { tool, .. } => client::parse_tool_spec(tool).0,
+            Operation::Read { resource, .. } | Operation::Download { resource, .. } => {
+                client::parse_resource_spec(resource).0
+            }
+            Operation::Prompt { name, .. } => client::parse_prompt_spec(name).0,
+            Operation::Complete { kind, name, .. } => match kind {
+                CompletionTarget::Prompt => client::parse_prompt_spec(name).0,
+                CompletionTarget::Template => client::parse_resource_spec(name).0,
+            },
+            Operation::Info { kind, name } => match kind {
+                InfoKind::Tool => client::parse_tool_spec(name).0,
+                InfoKind::Resource | InfoKind::Template => client::parse_resource_spec(name).0,
+                InfoKind::Prompt => client::parse_prompt_spec(name).0,
+            },
+            Operation::List { .. } => None,
+        }
+    }
+}
+
+/// 加载配置，只连接需要的服务器，执行一次操作后返回进程退出码
+pub async fn run(mut manager: ClientManager, op: Operation) -> i32 {
+    // 非交互模式下通知和提示写到 stderr，保证 stdout 只有结果
+    console::global().use_stderr(true);
+    if let Some(mut events) = manager.take_events() {
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                console::global().print_above(&event.render());
+            }
+        });
+    }
+    let operation = async {
+        connect_servers(&mut manager, op.target_server()).await?;
+        execute(&manager, op).await
+    };
+    let result = client::cancellable(operation).await;
+    manager.shutdown().await;
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            manager.report_error("Error:", &e);
+            client::exit_code(&e)
+        }
+    }
+}
+
+/// 执行 config 子命令后返回进程退出码
+pub fn run_config(manager: &ClientManager, action: ConfigAction) -> i32 {
+    let result = match action {
+        ConfigAction::Check => manager.check_config(),
+        ConfigAction::Import { file, name, dry_run } => manager.import_config(&file, name.as_deref(), dry_run),
+        ConfigAction::Export { format, file } => manager.export_config(format, file.as_deref()),
+    };
+    match result {
+        Ok(()) => 0,
+        // 检查结果已经输出，这里只需要退出码
+        Err(e) if matches!(e.downcast_ref::<ClientError>(), Some(ClientError::InvalidConfig(_))) => client::exit_code(&e),
+        Err(e) => {
+            manager.report_error("Error:", &e);
+            client::exit_code(&e)
+        }
+    }
+}
+
+async fn connect_servers(manager: &mut ClientManager, only: Option<&str>) -> Result<()> {
+    let config = McpConfig::load()?;
+    manager.apply_sampling_config(config.sampling.as_ref())?;
+    manager.apply_elicitation_config(config.elicitation.as_ref())?;
+    manager.apply_roots_config(&config)?;
+
+    if let Some(name) = only {
+        match config.mcp_servers.get(name) {
+            None => return Err(ClientError::ServerNotFound(name.to_string()).into()),
+            Some(server_conf) if server_conf.is_disabled() => {
+                return Err(ClientError::Usage(format!("Server '{}' is disabled in the config", name)).into());
+            }
+            Some(_) => {}
+        }
+    }
+
+    let selected = config
+        .mcp_servers
+        .iter()
+        .filter(|(name, server_conf)| only.is_none_or(|o| o == name.as_str()) && !server_conf.is_disabled());
+    let summary = manager.connect_all(selected).await;
+    for report in &summary.servers {
+        let Some(error) = &report.error else {
+            continue;
+        };
+        // 指定了服务器时连接失败即为致命错误
+        if only.is_some() {
+            anyhow::bail!("Failed to connect to '{}': {}", report.server, error);
+        }
+        eprintln!("{} '{}': {}", "Failed to connect:".red(), report.server, error);
+    }
+    Ok(())
+}
+
+async fn execute(manager: &ClientManager, op: Operation) -> Result<()> {
+    match op {
+        Operation::Call { tool, args, timeout } => {
+            let args = parse_call_args(args)?;
+            manager.call_tool(&tool, args, timeout.map(Duration::from_secs)).await
+        }
+        Operation::Read { resource, params } if params.is_empty() => manager.read_resource(&resource).await,
+        Operation::Read { resource, params } => {
+            let params: Vec<&str> = params.iter().map(String::as_str).collect();
+            manager.read_template(&resource, &params).await
+        }
+        Operation::Download { resource, path } => manager.download_resource(&resource, &path).await,
+        Operation::List { kind, view } => match kind {
+            ListKind::Mcp => manager.emit(&manager.server_listing()),
+            ListKind::Tool => manager.list_tools(view).await,
+            ListKind::Resource => manager.list_resources(view).await,
+            ListKind::Template => manager.list_templates(view).await,
+            ListKind::Prompt => manager.list_prompts(view).await,
+        },
+        Operation::Info { kind, name } => match kind {
+            InfoKind::Tool => manager.tool_info(&name),
+            InfoKind::Resource => manager.resource_info(&name),
+            InfoKind::Template => manager.template_info(&name),
+            InfoKind::Prompt => manager.prompt_info(&name),
+        },
+        Operation::Complete { kind, name, argument, value } => manager.complete(kind, &name, &argument, &value).await,
+        Operation::Prompt { name, args } => {
+            let args = args
+                .iter()
+                .map(|arg| {
+                    arg.split_once('=')
+                        .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+                        .ok_or_else(|| {
+                            ClientError::Usage(format!("Invalid prompt argument '{}', expected key=value", arg)).into()
+                        })
+                })
+                .collect::<Result<HashMap<String, String>>>()?;
+            manager.use_prompt_with_args(&name, &args).await
+        }
+    }
+}
+
+/// 工具参数: 省略时为 `{}`，`-` 表示从标准输入读取 JSON
+fn parse_call_args(args: Option<String>) -> Result<serde_json::Value> {
+    let json_str = match args.as_deref() {
+        None => "{}".to_string(),
+        Some("-") => {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)?;
+            buf
+        }
+        Some(s) => s.to_string(),
+    };
+    serde_json::from_str(&json_str)
+        .map_err(|e| ClientError::Usage(format!("Invalid JSON arguments: {}", e)).into())
+}
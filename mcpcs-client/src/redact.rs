@@ -0,0 +1,260 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use serde_json::Value;
+
+/// 替换秘密值的占位符
+pub const MASK: &str = "***";
+/// 太短的值不登记，避免把普通文本也替换掉
+const MIN_SECRET_LEN: usize = 4;
+/// 名称分段后命中其中之一即视为敏感 (请求头、环境变量、配置字段)
+const SENSITIVE_SEGMENTS: &[&str] = &[
+    "auth", "authorization", "token", "secret", "password", "passwd", "pwd", "key", "apikey", "credential", "credentials",
+    "cookie", "session", "private", "signature",
+];
+/// auth 对象中保存秘密的字段
+const AUTH_SECRET_FIELDS: &[&str] = &["token", "password", "value", "client_secret"];
+/// 名称像秘密、但保存的是文件路径的配置字段
+const PATH_FIELDS: &[&str] = &["client_key"];
+
+static SHOW_SECRETS: AtomicBool = AtomicBool::new(false);
+/// 从配置、密钥存储和令牌来源得到的秘密值
+static SECRETS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// `--show-secrets`：本地排查问题时原样输出
+pub fn set_show_secrets(show: bool) {
+    SHOW_SECRETS.store(show, Ordering::Relaxed);
+}
+
+/// 登记一个秘密值，之后出现在任何诊断输出中都会被替换
+pub fn register(value: &str) {
+    let value = value.trim();
+    if value.len() >= MIN_SECRET_LEN {
+        SECRETS.lock().unwrap().insert(value.to_string());
+    }
+}
+
+/// 请求头、环境变量或配置字段的名称是否表示秘密，如 Authorization、X-Api-Key、GITHUB_TOKEN
+pub fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|segment| SENSITIVE_SEGMENTS.contains(&segment))
+        || ["token", "secret", "password", "apikey"].iter().any(|word| name.contains(word))
+}
+
+/// 所有日志、跟踪和错误输出打印前都经过这里：替换登记过的秘密、
+/// `Bearer`/`Basic` 凭据和 URL 中敏感的查询参数
+pub fn redact(text: &str) -> String {
+    if SHOW_SECRETS.load(Ordering::Relaxed) {
+        return text.to_string();
+    }
+    let mut text = text.to_string();
+    {
+        let secrets = SECRETS.lock().unwrap();
+        // 先替换较长的值，避免一个秘密是另一个的前缀时留下残余
+        let mut ordered: Vec<&String> = secrets.iter().collect();
+        ordered.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        for secret in ordered {
+            if text.contains(secret.as_str()) {
+                text = text.replace(secret.as_str(), MASK);
+            }
+        }
+    }
+    let text = mask_credentials(&text);
+    mask_query_params(&text)
+}
+
+/// 登记 JSON 配置中所有敏感字段的值；未展开的 `${VAR}` 和 `secret://` 引用跳过
+pub fn register_json(value: &Value) {
+    let mut value = value.clone();
+    visit_secrets(&mut value, None, &mut |secret| {
+        if !is_reference(secret) {
+            register(secret);
+        }
+    });
+}
+
+/// 把 JSON 配置中敏感字段的值换成占位符；`${VAR}` 和 `secret://` 引用本身不是秘密，保留原样
+pub fn redact_json(value: &mut Value) {
+    if SHOW_SECRETS.load(Ordering::Relaxed) {
+        return;
+    }
+    visit_secrets(value, None, &mut |secret| {
+        if !is_reference(secret) {
+            *secret = MASK.to_string();
+        }
+    });
+}
+
+fn visit_secrets(value: &mut Value, parent: Option<&str>, f: &mut impl FnMut(&mut String)) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                let secret = match parent {
+                    Some("auth") => AUTH_SECRET_FIELDS.contains(&key.as_str()),
+                    _ => is_sensitive(key) && !PATH_FIELDS.contains(&key.as_str()),
+                };
+                match item {
+                    Value::String(s) if secret => f(s),
+                    _ => visit_secrets(item, Some(key), f),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                visit_secrets(item, parent, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_reference(value: &str) -> bool {
+    value.starts_with("secret://") || (value.starts_with("${") && value.ends_with('}') && value.matches("${").count() == 1)
+}
+
+/// `Bearer <token>` 和 `Basic <credentials>` 中的凭据
+fn mask_credentials(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        let found = ["bearer ", "basic "]
+            .iter()
+            .filter_map(|scheme| lower.find(scheme).map(|i| (i, scheme.len())))
+            .min();
+        let Some((start, len)) = found else { break };
+        let value_start = start + len;
+        let value_len = rest[value_start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | ')' | '}'))
+            .unwrap_or(rest.len() - value_start);
+        masked.push_str(&rest[..value_start]);
+        // 像凭据的才替换，"basic auth" 这样的普通文字和质询参数 (realm="...") 保持原样
+        let value = &rest[value_start..value_start + value_len];
+        let is_word = value.chars().all(|c| c.is_ascii_lowercase());
+        let is_param = value
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_'));
+        if value.len() >= MIN_SECRET_LEN && value != MASK && !is_word && !is_param {
+            masked.push_str(MASK);
+        } else {
+            masked.push_str(value);
+        }
+        rest = &rest[value_start + value_len..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// URL 中 `?api_key=...`、`&access_token=...` 这类查询参数的值
+fn mask_query_params(text: &str) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find(['?', '&']) {
+        masked.push_str(&rest[..=i]);
+        rest = &rest[i + 1..];
+        let name_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+            .unwrap_or(rest.len());
+        if name_len == 0 || !rest[name_len..].starts_with('=') || !is_sensitive(&rest[..name_len]) {
+            continue;
+        }
+        masked.push_str(&rest[..=name_len]);
+        rest = &rest[name_len + 1..];
+        let value_len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '&' | '#' | '"' | '\'' | ')'))
+            .unwrap_or(rest.len());
+        if value_len > 0 {
+            masked.push_str(MASK);
+        }
+        rest = &rest[value_len..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sensitive_names() {
+        for name in ["Authorization", "X-Api-Key", "GITHUB_TOKEN", "api_key", "apikey", "client_secret", "DB_PASSWORD", "Cookie", "accessToken"] {
+            assert!(is_sensitive(name), "{}", name);
+        }
+        for name in ["Accept", "Content-Type", "PORT", "PATH", "url", "keyboard_layout", "monkey"] {
+            assert!(!is_sensitive(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn registered_secrets_are_replaced_longest_first() {
+        register("redact-test-short");
+        register("redact-test-short-and-longer");
+        register("ab");
+        assert_eq!(redact("value redact-test-short-and-longer here"), "value *** here");
+        assert_eq!(redact("value redact-test-short"), "value ***");
+        // 太短的值不登记
+        assert_eq!(redact("ab cd"), "ab cd");
+    }
+
+    #[test]
+    fn credentials_and_query_params_are_masked() {
+        assert_eq!(redact("Authorization: Bearer eyJhbGciOi.x.y"), "Authorization: Bearer ***");
+        assert_eq!(redact("header Basic dXNlcjpwYXNz, next"), "header Basic ***, next");
+        // 普通文字和质询参数保留
+        assert_eq!(redact("uses basic auth"), "uses basic auth");
+        assert_eq!(redact(r#"Bearer realm="mcp""#), r#"Bearer realm="mcp""#);
+        assert_eq!(redact("Bearer error=invalid_token"), "Bearer error=invalid_token");
+        // base64 的填充不是参数
+        assert_eq!(redact("Basic dXNlcjpwYXNzd29yZA=="), "Basic ***");
+        assert_eq!(
+            redact("GET https://h/api?api_key=abc123&page=2#top failed"),
+            "GET https://h/api?api_key=***&page=2#top failed"
+        );
+        assert_eq!(redact("https://h/?access_token=t0k&x=1"), "https://h/?access_token=***&x=1");
+    }
+
+    #[test]
+    fn register_json_uses_field_names_and_skips_references() {
+        register_json(&json!({
+            "mcpServers": {
+                "a": {
+                    "auth_token": "redact-json-plain-token",
+                    "headers": { "X-Api-Key": "redact-json-header", "Accept": "application/json" },
+                    "env": { "GITHUB_TOKEN": "${GITHUB_TOKEN}" },
+                    "auth": { "type": "basic", "username": "redact-json-user", "password": "redact-json-pass" },
+                }
+            }
+        }));
+        assert_eq!(
+            redact("redact-json-plain-token redact-json-header redact-json-pass"),
+            "*** *** ***"
+        );
+        assert_eq!(redact("application/json redact-json-user"), "application/json redact-json-user");
+        assert_eq!(redact("${GITHUB_TOKEN}"), "${GITHUB_TOKEN}");
+    }
+
+    #[test]
+    fn redact_json_masks_values_but_keeps_references() {
+        let mut value = json!({
+            "auth_token": "plain",
+            "env": { "API_KEY": "${API_KEY}", "SECRET": "secret://github", "HOME": "/home/me" },
+            "auth": { "type": "oauth", "client_id": "id", "client_secret": "cs" },
+            "args": [{ "token": "nested" }],
+            "client_key": "/etc/mcp/client.key",
+        });
+        redact_json(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "auth_token": MASK,
+                "env": { "API_KEY": "${API_KEY}", "SECRET": "secret://github", "HOME": "/home/me" },
+                "auth": { "type": "oauth", "client_id": "id", "client_secret": MASK },
+                "args": [{ "token": MASK }],
+                "client_key": "/etc/mcp/client.key",
+            })
+        );
+    }
+}
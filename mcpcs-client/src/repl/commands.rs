@@ -1,8 +1,11 @@
+use std::time::Duration;
 use anyhow::Result;
+use clap::ValueEnum;
 use colored::Colorize;
 
-use crate::client::ClientManager;
-use crate::config::McpConfig;
+use crate::client::{ClientError, ClientManager, CompletionTarget, ListView, OutputFormat};
+use crate::config::{Dialect, McpConfig};
+use crate::redact::redact;
 
 pub async fn handle_reload(manager: &mut ClientManager) -> Result<()> {
     println!("{}", "Reloading configuration...".dimmed());
@@ -11,7 +14,7 @@ pub async fn handle_reload(manager: &mut ClientManager) -> Result<()> {
             manager.load_from_config(&config).await?;
         }
         Err(e) => {
-            eprintln!("{} {}", "Failed to load config:".red(), e);
+            eprintln!("{} {}", "Failed to load config:".red(), redact(&e.to_string()));
         }
     }
     Ok(())
@@ -19,56 +22,92 @@ pub async fn handle_reload(manager: &mut ClientManager) -> Result<()> {
 
 pub async fn handle_list(manager: &ClientManager, parts: &[&str]) -> Result<()> {
     if parts.len() > 1 {
+        let view = match ListView::parse(&parts[2..]) {
+            Ok(view) => view,
+            Err(e) => {
+                manager.report_error("Error:", &e);
+                return Ok(());
+            }
+        };
         match parts[1] {
             "mcp" => {
-                let servers = manager.list_servers();
-                if servers.is_empty() {
-                    println!("{}", "No connected servers.".yellow());
-                } else {
-                    for s in servers {
-                        println!("{} {}", "-".dimmed(), s.cyan());
-                    }
+                if let Err(e) = manager.emit(&manager.server_listing()) {
+                    manager.report_error("Error listing servers:", &e);
                 }
             }
             "tool" => {
-                if let Err(e) = manager.list_tools().await {
-                    eprintln!("{} {:?}", "Error listing tools:".red(), e);
+                if let Err(e) = manager.list_tools(view).await {
+                    manager.report_error("Error listing tools:", &e);
                 }
             }
             "resource" => {
-                if let Err(e) = manager.list_resources().await {
-                    eprintln!("{} {:?}", "Error listing resources:".red(), e);
+                if let Err(e) = manager.list_resources(view).await {
+                    manager.report_error("Error listing resources:", &e);
+                }
+            }
+            "template" => {
+                if let Err(e) = manager.list_templates(view).await {
+                    manager.report_error("Error listing resource templates:", &e);
                 }
             }
             "prompt" => {
-                if let Err(e) = manager.list_prompts().await {
-                    eprintln!("{} {:?}", "Error listing prompts:".red(), e);
+                if let Err(e) = manager.list_prompts(view).await {
+                    manager.report_error("Error listing prompts:", &e);
                 }
             }
-            _ => println!("{}", "Unknown list command. Usage: /list mcp | /list tool | /list resource | /list prompt".yellow()),
+            _ => println!("{}", "Unknown list command. Usage: /list mcp | /list tool | /list resource | /list template | /list prompt [--limit N] [--page-size N]".yellow()),
         }
     } else {
-        println!("{}", "Usage: /list mcp | /list tool | /list resource | /list prompt".yellow());
+        println!("{}", "Usage: /list mcp | /list tool | /list resource | /list template | /list prompt [--limit N] [--page-size N]".yellow());
     }
     Ok(())
 }
 
 pub async fn handle_call(manager: &ClientManager, input: &str, parts: &[&str]) -> Result<()> {
+    const USAGE: &str = "Usage: /call [--timeout SECS] <tool_name> [json_args]";
+    // --timeout 只能紧跟在 /call 之后，其余部分原样作为 JSON 参数
+    let (timeout, parts) = match parts.get(1) {
+        Some(&"--timeout") => match parts.get(2).and_then(|s| s.parse::<u64>().ok()).filter(|&secs| secs > 0) {
+            Some(secs) => (Some(Duration::from_secs(secs)), &parts[2..]),
+            None => {
+                println!("{}", "--timeout expects a whole number of seconds greater than 0".yellow());
+                return Ok(());
+            }
+        },
+        _ => (None, parts),
+    };
     if parts.len() < 2 {
-        println!("{}", "Usage: /call <tool_name> [json_args]".yellow());
+        println!("{}", USAGE.yellow());
         return Ok(());
     }
-    
+
     let tool_name = parts[1];
-    let args_start_index = input.find(tool_name).map(|i| i + tool_name.len()).unwrap_or(input.len());
+    // 从上一个词之后查找工具名，避免与 --timeout 的值重叠
+    let search_from = input.find(parts[0]).map(|i| i + parts[0].len()).unwrap_or(0);
+    let args_start_index = input[search_from..]
+        .find(tool_name)
+        .map(|i| search_from + i + tool_name.len())
+        .unwrap_or(input.len());
     let json_str = input[args_start_index..].trim();
-    
-    let json_arg = if json_str.is_empty() { "{}" } else { json_str };
 
-    match serde_json::from_str::<serde_json::Value>(json_arg) {
+    // 省略参数时按工具的 input_schema 逐项询问
+    if json_str.is_empty() {
+        match manager.build_tool_arguments(tool_name).await {
+            Ok(Some((tool, args))) => {
+                if let Err(e) = manager.call_tool(&tool, args, timeout).await {
+                    manager.report_error("Error calling tool:", &e);
+                }
+            }
+            Ok(None) => println!("{}", "Tool call cancelled".yellow()),
+            Err(e) => manager.report_error("Error calling tool:", &e),
+        }
+        return Ok(());
+    }
+
+    match serde_json::from_str::<serde_json::Value>(json_str) {
         Ok(args) => {
-            if let Err(e) = manager.call_tool(tool_name, args).await {
-                eprintln!("{} {:?}", "Error calling tool:".red(), e);
+            if let Err(e) = manager.call_tool(tool_name, args, timeout).await {
+                manager.report_error("Error calling tool:", &e);
             }
         }
         Err(e) => {
@@ -81,11 +120,17 @@ pub async fn handle_call(manager: &ClientManager, input: &str, parts: &[&str]) -
 pub async fn handle_read(manager: &ClientManager, parts: &[&str]) -> Result<()> {
     if parts.len() >= 3 && parts[1] == "resource" {
         let resource_uri = parts[2];
-        if let Err(e) = manager.read_resource(resource_uri).await {
-            eprintln!("{} {:?}", "Error reading resource:".red(), e);
+        // 带 key=value 参数时按资源模板展开
+        let result = if parts.len() > 3 {
+            manager.read_template(resource_uri, &parts[3..]).await
+        } else {
+            manager.read_resource(resource_uri).await
+        };
+        if let Err(e) = result {
+            manager.report_error("Error reading resource:", &e);
         }
     } else {
-        println!("{}", "Usage: /read resource <uri> | /read resource <server>/<uri>".yellow());
+        println!("{}", "Usage: /read resource <uri> | /read resource <server>/<uri> | /read resource <server>/<template> key=value ...".yellow());
     }
     Ok(())
 }
@@ -95,7 +140,7 @@ pub async fn handle_down(manager: &ClientManager, parts: &[&str]) -> Result<()>
         let resource_uri = parts[2];
         let local_path = parts[3];
         if let Err(e) = manager.download_resource(resource_uri, local_path).await {
-            eprintln!("{} {:?}", "Error downloading resource:".red(), e);
+            manager.report_error("Error downloading resource:", &e);
         }
     } else {
         println!("{}", "Usage: /down resource <uri> <local_path> | /down resource <server>/<uri> <local_path>".yellow());
@@ -108,39 +153,98 @@ pub async fn handle_info(manager: &ClientManager, parts: &[&str]) -> Result<()>
         match parts[1] {
             "tool" => {
                 let tool_name = parts[2];
-                if let Err(e) = manager.tool_info(tool_name).await {
-                    eprintln!("{} {:?}", "Error getting tool info:".red(), e);
+                if let Err(e) = manager.tool_info(tool_name) {
+                    manager.report_error("Error getting tool info:", &e);
                 }
             }
             "resource" => {
                 let resource_uri = parts[2];
-                if let Err(e) = manager.resource_info(resource_uri).await {
-                    eprintln!("{} {:?}", "Error getting resource info:".red(), e);
+                if let Err(e) = manager.resource_info(resource_uri) {
+                    manager.report_error("Error getting resource info:", &e);
+                }
+            }
+            "template" => {
+                let template_name = parts[2];
+                if let Err(e) = manager.template_info(template_name) {
+                    manager.report_error("Error getting resource template info:", &e);
                 }
             }
             "prompt" => {
                 let prompt_name = parts[2];
-                if let Err(e) = manager.prompt_info(prompt_name).await {
-                    eprintln!("{} {:?}", "Error getting prompt info:".red(), e);
+                if let Err(e) = manager.prompt_info(prompt_name) {
+                    manager.report_error("Error getting prompt info:", &e);
                 }
             }
-            _ => println!("{}", "Usage: /info tool <tool_name> | /info resource <uri>|<server>/<uri> | /info prompt <name>|<server>/<name>".yellow()),
+            _ => println!("{}", "Usage: /info tool <tool_name> | /info resource <uri>|<server>/<uri> | /info template <name>|<server>/<name> | /info prompt <name>|<server>/<name>".yellow()),
         }
     } else {
-        println!("{}", "Usage: /info tool <tool_name> | /info resource <uri>|<server>/<uri> | /info prompt <name>|<server>/<name>".yellow());
+        println!("{}", "Usage: /info tool <tool_name> | /info resource <uri>|<server>/<uri> | /info template <name>|<server>/<name> | /info prompt <name>|<server>/<name>".yellow());
     }
     Ok(())
 }
 
-pub fn handle_newconfig(parts: &[&str]) {
-    if parts.len() < 2 {
-        println!("{}", "Usage: /newconfig <name>".yellow());
-    } else {
-        let name = parts[1];
-        match McpConfig::create_new(name) {
+pub fn handle_newconfig(manager: &ClientManager, parts: &[&str]) {
+    match parts {
+        [_, name] => match McpConfig::create_new(name) {
             Ok(path) => println!("{} {}", "Created config file:".green(), path.display()),
-            Err(e) => eprintln!("{} {}", "Failed to create config:".red(), e),
+            Err(e) => eprintln!("{} {}", "Failed to create config:".red(), redact(&e.to_string())),
+        },
+        // 从其他宿主的配置文件创建
+        [_, name, source] => {
+            if let Err(e) = manager.import_config(source, Some(name), false) {
+                manager.report_error("Failed to create config:", &e);
+            }
         }
+        _ => println!("{}", "Usage: /newconfig <name> [file-to-import]".yellow()),
+    }
+}
+
+pub fn handle_config(manager: &ClientManager, parts: &[&str]) {
+    let usage = "Usage: /config check | import <file> [--name N] [--dry-run] | export [--format D] [file]";
+    let result = match parts.get(1).copied() {
+        Some("check") => match manager.check_config() {
+            Err(e) if matches!(e.downcast_ref::<ClientError>(), Some(ClientError::InvalidConfig(_))) => Ok(()),
+            other => other,
+        },
+        Some("import") => {
+            let (mut file, mut name, mut dry_run) = (None, None, false);
+            let mut args = parts[2..].iter();
+            while let Some(arg) = args.next() {
+                match *arg {
+                    "--dry-run" => dry_run = true,
+                    "--name" => name = args.next().copied(),
+                    other => file = Some(other),
+                }
+            }
+            match file {
+                Some(file) => manager.import_config(file, name, dry_run),
+                None => return println!("{}", usage.yellow()),
+            }
+        }
+        Some("export") => {
+            let (mut format, mut output) = (Dialect::Mcpcs, None);
+            let mut args = parts[2..].iter();
+            while let Some(arg) = args.next() {
+                match *arg {
+                    "--format" => match args.next().map(|d| Dialect::from_str(d, true)) {
+                        Some(Ok(dialect)) => format = dialect,
+                        _ => {
+                            let names: Vec<String> = Dialect::value_variants()
+                                .iter()
+                                .filter_map(|d| d.to_possible_value().map(|v| v.get_name().to_string()))
+                                .collect();
+                            return println!("{} {}", "--format expects one of:".yellow(), names.join(", "));
+                        }
+                    },
+                    other => output = Some(other),
+                }
+            }
+            manager.export_config(format, output)
+        }
+        _ => return println!("{}", usage.yellow()),
+    };
+    if let Err(e) = result {
+        manager.report_error("Config error:", &e);
     }
 }
 
@@ -153,7 +257,7 @@ pub async fn handle_use(manager: &ClientManager, parts: &[&str]) -> Result<()> {
             String::new()
         };
         if let Err(e) = manager.use_prompt(prompt_name, &args).await {
-            eprintln!("{} {:?}", "Error using prompt:".red(), e);
+            manager.report_error("Error using prompt:", &e);
         }
     } else {
         println!("{}", "Usage: /use prompt <name> [key=value...]".yellow());
@@ -161,6 +265,154 @@ pub async fn handle_use(manager: &ClientManager, parts: &[&str]) -> Result<()> {
     Ok(())
 }
 
+pub async fn handle_complete(manager: &ClientManager, parts: &[&str]) -> Result<()> {
+    let target = match parts.get(1) {
+        Some(&"prompt") => CompletionTarget::Prompt,
+        Some(&"template") => CompletionTarget::Template,
+        _ => {
+            println!("{}", "Usage: /complete prompt <name> <arg> [partial] | /complete template <name> <variable> [partial]".yellow());
+            return Ok(());
+        }
+    };
+    if parts.len() < 4 {
+        println!("{}", "Usage: /complete prompt <name> <arg> [partial] | /complete template <name> <variable> [partial]".yellow());
+        return Ok(());
+    }
+    let partial = parts.get(4).copied().unwrap_or("");
+    if let Err(e) = manager.complete(target, parts[2], parts[3], partial).await {
+        manager.report_error("Error completing argument:", &e);
+    }
+    Ok(())
+}
+
+pub fn handle_format(manager: &mut ClientManager, parts: &[&str]) {
+    match parts.get(1).map(|f| f.to_ascii_lowercase()).as_deref() {
+        None => println!("{} {}", "Output format:".dimmed(), manager.output().as_str().cyan()),
+        Some("json") => {
+            manager.set_output(OutputFormat::Json);
+            println!("{} {}", "Output format set to".green(), "json".cyan());
+        }
+        Some("text") => {
+            manager.set_output(OutputFormat::Text);
+            println!("{} {}", "Output format set to".green(), "text".cyan());
+        }
+        Some(_) => println!("{}", "Usage: /format [json|text]".yellow()),
+    }
+}
+
+pub async fn handle_subscribe(manager: &ClientManager, parts: &[&str]) -> Result<()> {
+    if parts.len() >= 3 && parts[1] == "resource" {
+        if let Err(e) = manager.subscribe_resource(parts[2], parts.get(3).copied()).await {
+            manager.report_error("Error subscribing to resource:", &e);
+        }
+    } else {
+        println!("{}", "Usage: /subscribe resource <uri>|<server>/<uri> [local_file]".yellow());
+    }
+    Ok(())
+}
+
+pub async fn handle_unsubscribe(manager: &ClientManager, parts: &[&str]) -> Result<()> {
+    if parts.len() >= 3 && parts[1] == "resource" {
+        if let Err(e) = manager.unsubscribe_resource(parts[2]).await {
+            manager.report_error("Error unsubscribing from resource:", &e);
+        }
+    } else {
+        println!("{}", "Usage: /unsubscribe resource <uri>|<server>/<uri>".yellow());
+    }
+    Ok(())
+}
+
+pub fn handle_subscriptions(manager: &ClientManager) {
+    if let Err(e) = manager.emit(&manager.subscription_listing()) {
+        manager.report_error("Error listing subscriptions:", &e);
+    }
+}
+
+pub async fn handle_roots(manager: &ClientManager, parts: &[&str]) -> Result<()> {
+    let server = parts.get(3).copied();
+    if let Some(server) = server {
+        if !manager.clients.contains_key(server) {
+            eprintln!("{} {}", "Server not found:".red(), server);
+            return Ok(());
+        }
+    }
+
+    match (parts.get(1).copied(), parts.get(2).copied()) {
+        (Some("list") | None, _) => {
+            if let Err(e) = manager.emit(&manager.root_listing()) {
+                manager.report_error("Error listing roots:", &e);
+            }
+        }
+        (Some("add"), Some(path)) => match manager.add_root(path, server).await {
+            Ok(root) => println!("{} {}", "Added root:".green(), root.uri.cyan()),
+            Err(e) => manager.report_error("Error adding root:", &e),
+        },
+        (Some("rm"), Some(path)) => match manager.remove_root(path, server).await {
+            Ok(root) => println!("{} {}", "Removed root:".green(), root.uri.cyan()),
+            Err(e) => manager.report_error("Error removing root:", &e),
+        },
+        _ => println!("{}", "Usage: /roots list | /roots add <path|uri> [server] | /roots rm <path|uri|name> [server]".yellow()),
+    }
+    Ok(())
+}
+
+pub async fn handle_connect(manager: &mut ClientManager, parts: &[&str]) -> Result<()> {
+    let Some(name) = parts.get(1) else {
+        println!("{}", "Usage: /connect <server>".yellow());
+        return Ok(());
+    };
+    match manager.connect_server(name).await {
+        Ok(summary) => manager.emit(&summary)?,
+        Err(e) => manager.report_error("Error connecting:", &e),
+    }
+    Ok(())
+}
+
+pub async fn handle_disconnect(manager: &mut ClientManager, parts: &[&str]) -> Result<()> {
+    let Some(name) = parts.get(1) else {
+        println!("{}", "Usage: /disconnect <server>".yellow());
+        return Ok(());
+    };
+    if let Err(e) = manager.disconnect_server(name).await {
+        manager.report_error("Error disconnecting:", &e);
+    }
+    Ok(())
+}
+
+pub async fn handle_restart(manager: &mut ClientManager, parts: &[&str]) -> Result<()> {
+    let Some(name) = parts.get(1) else {
+        println!("{}", "Usage: /restart <server>".yellow());
+        return Ok(());
+    };
+    match manager.restart_server(name).await {
+        Ok(summary) => manager.emit(&summary)?,
+        Err(e) => manager.report_error("Error restarting:", &e),
+    }
+    Ok(())
+}
+
+pub fn handle_status(manager: &ClientManager) {
+    if let Err(e) = manager.emit(&manager.status_listing()) {
+        manager.report_error("Error showing status:", &e);
+    }
+}
+
+pub async fn handle_retry(manager: &mut ClientManager, parts: &[&str]) -> Result<()> {
+    match manager.retry_failed(parts.get(1).copied()).await {
+        Ok(summary) if summary.servers.is_empty() => println!("{}", "No failed servers to retry.".dimmed()),
+        Ok(summary) => manager.emit(&summary)?,
+        Err(e) => manager.report_error("Error retrying:", &e),
+    }
+    Ok(())
+}
+
+pub async fn handle_refresh(manager: &ClientManager, parts: &[&str]) -> Result<()> {
+    if let Err(e) = manager.refresh_catalog(parts.get(1).copied()).await {
+        manager.report_error("Error refreshing:", &e);
+    }
+    Ok(())
+}
+
 pub fn handle_unknown(cmd: &str) {
     println!("{} {}", "Unknown command:".yellow(), cmd);
 }
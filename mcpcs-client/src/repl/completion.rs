@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap};
+use rustyline::completion::Pair;
+
+use crate::client::{CompletionSource, CompletionTarget, NameKind};
+use crate::console::LineCompleter;
+
+/// 按 Tab 时最多列出的补全值
+const MAX_CANDIDATES: usize = 20;
+
+/// REPL 的所有命令，补全第一个词时使用
+const COMMANDS: &[&str] = &[
+    "/reload", "/connect", "/disconnect", "/restart", "/status", "/retry", "/refresh",
+    "/list", "/call", "/read", "/down", "/info", "/use", "/complete", "/newconfig", "/config", "/subscribe",
+    "/unsubscribe", "/subscriptions", "/roots", "/format", "/exit", "/quit",
+];
+
+/// REPL 的 Tab 补全：命令、子命令、服务器名，以及来自已连接服务器的工具/资源/提示词名称和参数
+pub struct ReplCompleter {
+    source: CompletionSource,
+}
+
+impl ReplCompleter {
+    pub fn new(source: CompletionSource) -> Self {
+        Self { source }
+    }
+
+    /// 按已输入的词决定第 n 个词的候选
+    fn candidates(&self, tokens: &[&str], word: &str) -> Vec<Pair> {
+        match tokens {
+            [] => words(COMMANDS, word),
+            ["/list"] => words(&["mcp", "tool", "resource", "template", "prompt"], word),
+            ["/list", _, ..] => words(&["--limit", "--page-size"], word),
+            ["/info"] => words(&["tool", "resource", "template", "prompt"], word),
+            ["/info", "tool"] | ["/call"] | ["/call", "--timeout", _] => self.names(&[NameKind::Tool], word),
+            ["/info", "resource"] | ["/down", "resource"] | ["/subscribe", "resource"] | ["/unsubscribe", "resource"] => {
+                self.names(&[NameKind::Resource], word)
+            }
+            ["/info", "template"] | ["/complete", "template"] => self.names(&[NameKind::Template], word),
+            ["/info", "prompt"] | ["/use", "prompt"] | ["/complete", "prompt"] => self.names(&[NameKind::Prompt], word),
+            ["/read"] | ["/down"] | ["/subscribe"] | ["/unsubscribe"] => words(&["resource"], word),
+            ["/read", "resource"] => self.names(&[NameKind::Resource, NameKind::Template], word),
+            ["/read", "resource", spec, earlier @ ..] => self.key_values(CompletionTarget::Template, spec, earlier, word),
+            ["/use"] => words(&["prompt"], word),
+            ["/use", "prompt", spec, earlier @ ..] => self.key_values(CompletionTarget::Prompt, spec, earlier, word),
+            ["/complete"] => words(&["prompt", "template"], word),
+            ["/complete", "prompt", spec] => self.argument_names(CompletionTarget::Prompt, spec, word),
+            ["/complete", "template", spec] => self.argument_names(CompletionTarget::Template, spec, word),
+            ["/config"] => words(&["check", "import", "export"], word),
+            ["/config", "export", .., "--format"] => {
+                words(&["mcpcs", "claude-desktop", "claude-code", "cursor", "vscode", "windsurf", "cline"], word)
+            }
+            ["/roots"] => words(&["list", "add", "rm"], word),
+            ["/format"] => words(&["json", "text"], word),
+            ["/connect" | "/disconnect" | "/restart" | "/retry" | "/refresh"] => {
+                let servers = self.source.servers();
+                words(&servers.iter().map(String::as_str).collect::<Vec<_>>(), word)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// 名称只在一个服务器上出现时补全裸名称，有冲突时补全 server/name；已写出 server/ 时只补该服务器的
+    fn names(&self, kinds: &[NameKind], word: &str) -> Vec<Pair> {
+        let mut providers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for kind in kinds {
+            for (server, name) in self.source.names(*kind) {
+                providers.entry(name).or_default().push(server);
+            }
+        }
+        let mut candidates = Vec::new();
+        for (name, servers) in &providers {
+            for server in servers {
+                let qualified = format!("{}/{}", server, name);
+                if qualified.starts_with(word) && (servers.len() > 1 || word.starts_with(&format!("{}/", server))) {
+                    candidates.push(qualified);
+                }
+            }
+            if servers.len() == 1 && name.starts_with(word) {
+                candidates.push(name.clone());
+            }
+        }
+        candidates.dedup();
+        candidates.into_iter().map(|c| Pair { replacement: format!("{} ", c), display: c }).collect()
+    }
+
+    fn argument_names(&self, target: CompletionTarget, spec: &str, word: &str) -> Vec<Pair> {
+        let names = self.source.arguments(target, spec);
+        words(&names.iter().map(String::as_str).collect::<Vec<_>>(), word)
+    }
+
+    /// `key=value` 参数：还没写到 = 时补全尚未给出的参数名，之后向服务器请求值的补全
+    fn key_values(&self, target: CompletionTarget, spec: &str, earlier: &[&str], word: &str) -> Vec<Pair> {
+        let given: HashMap<String, String> = earlier
+            .iter()
+            .filter_map(|arg| arg.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.trim_matches(['"', '\'']).to_string()))
+            .collect();
+
+        let Some((key, partial)) = word.split_once('=') else {
+            return self
+                .source
+                .arguments(target, spec)
+                .into_iter()
+                .filter(|name| name.starts_with(word) && !given.contains_key(name))
+                .map(|name| Pair { display: name.clone(), replacement: format!("{}=", name) })
+                .collect();
+        };
+
+        let partial = partial.trim_start_matches(['"', '\'']);
+        let Some(completion) = self.source.complete(target, spec, key, partial, given) else {
+            return Vec::new();
+        };
+        let shown = completion.values.len().min(MAX_CANDIDATES);
+        let mut pairs: Vec<Pair> = completion.values[..shown]
+            .iter()
+            .map(|value| Pair { display: value.clone(), replacement: format!("{}={}", key, quote(value)) })
+            .collect();
+
+        // 在最后一个候选后面标出还有多少没有列出
+        let hidden = completion.total.map(|t| t as usize).unwrap_or(completion.values.len()).saturating_sub(shown);
+        let more = match (hidden, completion.has_more.unwrap_or(false)) {
+            (0, false) => None,
+            (0, true) => Some("(more...)".to_string()),
+            (n, _) => Some(format!("(+{} more)", n)),
+        };
+        if let (Some(more), Some(last)) = (more, pairs.last_mut()) {
+            last.display = format!("{} {}", last.display, more);
+        }
+        pairs
+    }
+}
+
+impl LineCompleter for ReplCompleter {
+    fn complete(&self, line: &str, pos: usize) -> (usize, Vec<Pair>) {
+        let head = &line[..pos];
+        // 多行输入 (如长 JSON) 不做补全
+        if head.contains('\n') {
+            return (pos, Vec::new());
+        }
+        let start = head
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let tokens: Vec<&str> = head[..start].split_whitespace().collect();
+        (start, self.candidates(&tokens, &head[start..]))
+    }
+}
+
+/// 固定词表中以 word 开头的词，补全后加一个空格
+fn words(list: &[&str], word: &str) -> Vec<Pair> {
+    list.iter()
+        .filter(|w| w.starts_with(word))
+        .map(|w| Pair { display: w.to_string(), replacement: format!("{} ", w) })
+        .collect()
+}
+
+/// 含空白的值加上引号，与 /use prompt 的参数解析一致
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
@@ -3,19 +3,48 @@ use colored::Colorize;
 pub fn print_banner() {
     println!("{}", "mcpcs-client REPL".cyan().bold());
     println!("{}", "Commands:".yellow());
-    println!("  {}              - Reload configuration from ~/.mcpcsrs/mcps/*.json", "/reload".green());
+    println!("  {}              - Reload configuration; only changed servers are started, stopped or restarted", "/reload".green());
+    println!("  {} {}    - Start a configured server (including disabled ones)", "/connect".green(), "<server>".dimmed());
+    println!("  {} {} - Stop a server without removing it from the config", "/disconnect".green(), "<server>".dimmed());
+    println!("  {} {}    - Stop and start a server again", "/restart".green(), "<server>".dimmed());
+    println!("  {}              - Show connection state, ping latency, uptime and restarts", "/status".green());
+    println!("  {} {}     - Reconnect servers that failed to start (all by default)", "/retry".green(), "[server]".dimmed());
+    println!("  {} {}   - Re-fetch tools, resources and prompts (all servers by default)", "/refresh".green(), "[server]".dimmed());
     println!("  {}            - List connected MCP servers", "/list mcp".green());
     println!("  {}           - List available tools from all servers", "/list tool".green());
     println!("  {}       - List available resources from all servers", "/list resource".green());
+    println!("  {}       - List resource templates from all servers", "/list template".green());
     println!("  {}        - List available prompts from all servers", "/list prompt".green());
+    println!("  {}   {} - Show at most N entries, or page through N at a time", "/list ...".green(), "[--limit N] [--page-size N]".dimmed());
     println!("  {} {} - Call a tool with JSON arguments (use server/tool for conflicts)", "/call".green(), "<tool> <json>".dimmed());
+    println!("  {} {}      - Call a tool, prompting for each argument from its input schema", "/call".green(), "<tool>".dimmed());
+    println!("  {} {} - Cancel the call if it takes longer than <secs> (default: the server timeout)", "/call --timeout".green(), "<secs> <tool> ...".dimmed());
     println!("  {} {} - Read and display resource content", "/read resource".green(), "<uri>|<server>/<uri>".dimmed());
+    println!("  {} {} - Expand a resource template and read it", "/read resource".green(), "<server>/<template> key=value ...".dimmed());
     println!("  {} {} - Download resource to local file", "/down resource".green(), "<uri> <path>".dimmed());
     println!("  {} {}    - Show detailed info about a tool", "/info tool".green(), "<name>".dimmed());
     println!("  {} {} - Show detailed info about a resource", "/info resource".green(), "<uri>|<server>/<uri>".dimmed());
+    println!("  {} {} - Show detailed info about a resource template", "/info template".green(), "<name>|<server>/<name>".dimmed());
     println!("  {} {} - Show detailed info about a prompt", "/info prompt".green(), "<name>|<server>/<name>".dimmed());
-    println!("  {} {} - Generate and display prompt", "/use prompt".green(), "<name> [key=value...]".dimmed());
-    println!("  {} {}    - Create a new empty MCP configuration file", "/newconfig".green(), "<name>".dimmed());
+    println!("  {} {} - Generate and display prompt (Tab completes argument names and values)", "/use prompt".green(), "<name> [key=value...]".dimmed());
+    println!("  {} {} - Ask the server for values of a prompt argument", "/complete prompt".green(), "<name> <arg> [partial]".dimmed());
+    println!("  {} {} - Ask the server for values of a template variable", "/complete template".green(), "<name> <var> [partial]".dimmed());
+    println!("  {} {} - Create a config file, empty or imported from another host's file", "/newconfig".green(), "<name> [file]".dimmed());
+    println!("  {}        - Validate the config files and show every problem found", "/config check".green());
+    println!("  {} {} - Convert another host's mcpServers file (Claude, Cursor, VS Code, ...)", "/config import".green(), "<file> [--name N] [--dry-run]".dimmed());
+    println!("  {} {} - Write the merged config in another host's format", "/config export".green(), "[--format D] [file]".dimmed());
+    println!("  {} {} - Watch a resource; updates show a diff or are saved to the file", "/subscribe resource".green(), "<uri> [file]".dimmed());
+    println!("  {} {} - Stop watching a resource", "/unsubscribe resource".green(), "<uri>".dimmed());
+    println!("  {}       - List resource subscriptions", "/subscriptions".green());
+    println!("  {}          - List roots exposed to servers", "/roots list".green());
+    println!("  {} {} - Add or remove a root (global unless a server is given)", "/roots add|rm".green(), "<path> [server]".dimmed());
+    println!("  {}", "Roots added here are kept across /reload; roots from the config are reloaded from it".dimmed());
+    println!("  {} {} - Show or set the output format", "/format".green(), "[json|text]".dimmed());
     println!("  {}                - Exit the REPL", "/exit".green());
     println!();
+    println!("{}", "Editing:".yellow());
+    println!("  {}                  - Complete commands, servers, tool/resource/prompt names and arguments", "Tab".green());
+    println!("  {}      - Browse and search history (saved in ~/.mcpcsrs/history)", "Up/Down, Ctrl+R".green());
+    println!("  {}                - Inside an unclosed {{ or [, continues on the next line", "Enter".green());
+    println!();
 }
@@ -1,16 +1,19 @@
 mod help;
 mod commands;
+mod completion;
 
-use std::io::{self, Write};
+use std::path::PathBuf;
 use anyhow::Result;
 use colored::Colorize;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc::UnboundedReceiver;
 
-use crate::client::ClientManager;
+use crate::client::{cancellable, ClientError, ClientEvent, ClientManager};
 use crate::config::McpConfig;
+use crate::console;
+use crate::redact::redact;
 
-pub async fn run() -> Result<()> {
-    let mut manager = ClientManager::new();
+pub async fn run(manager: ClientManager) -> Result<()> {
+    let mut manager = manager;
     run_with_manager_internal(&mut manager, true).await
 }
 
@@ -23,54 +26,99 @@ async fn run_with_manager_internal(manager: &mut ClientManager, load_config: boo
 
     help::print_banner();
 
+    if let Some(events) = manager.take_events() {
+        spawn_event_printer(events);
+    }
+    console::global().enable_editor(completion::ReplCompleter::new(manager.completion_source()), history_path());
+    manager.enable_health_checks();
+
     if load_config {
         println!("{}", "Loading configuration...".dimmed());
         match McpConfig::load() {
             Ok(config) => {
-                manager.load_from_config(&config).await?;
+                let loaded = cancellable(manager.load_from_config(&config)).await;
+                report_cancelled(loaded)?;
             }
             Err(e) => {
-                eprintln!("{} {}", "Failed to load config:".red(), e);
+                eprintln!("{} {}", "Failed to load config:".red(), redact(&e.to_string()));
             }
         }
     }
 
-    let stdin = tokio::io::stdin();
-    let mut reader = BufReader::new(stdin);
-    let mut line = String::new();
-
-    loop {
-        print!("{} ", ">".cyan().bold());
-        io::stdout().flush()?;
-
-        line.clear();
-        let bytes_read = reader.read_line(&mut line).await?;
-        if bytes_read == 0 {
-            break;
-        }
-
+    let prompt = format!("{} ", ">".cyan().bold());
+    // 标准输入由 console 统一读取，采样等服务器请求也会借用它
+    while let Some(line) = console::global().read_command(&prompt).await {
         let input = line.trim();
         if input.is_empty() {
             continue;
         }
+        manager.apply_reconnects();
 
         let parts: Vec<&str> = input.split_whitespace().collect();
-        match parts[0] {
-            "/reload" => commands::handle_reload(manager).await?,
-            "/list" => commands::handle_list(&manager, &parts).await?,
-            "/call" => commands::handle_call(&manager, input, &parts).await?,
-            "/read" => commands::handle_read(&manager, &parts).await?,
-            "/down" => commands::handle_down(&manager, &parts).await?,
-            "/info" => commands::handle_info(&manager, &parts).await?,
-            "/use" => commands::handle_use(&manager, &parts).await?,
-            "/newconfig" => commands::handle_newconfig(&parts),
-            "/exit" | "/quit" => {
-                println!("{}", "Goodbye!".cyan());
-                break;
-            }
-            _ => commands::handle_unknown(parts[0]),
+        // Ctrl+C 放弃当前命令，回到提示符
+        if report_cancelled(cancellable(dispatch(manager, input, &parts)).await)? == Some(false) {
+            break;
         }
     }
 
     Ok(())
 }
+
+/// 执行一条命令；返回 false 表示退出
+async fn dispatch(manager: &mut ClientManager, input: &str, parts: &[&str]) -> Result<bool> {
+    match parts[0] {
+        "/reload" => commands::handle_reload(manager).await?,
+        "/connect" => commands::handle_connect(manager, parts).await?,
+        "/disconnect" => commands::handle_disconnect(manager, parts).await?,
+        "/restart" => commands::handle_restart(manager, parts).await?,
+        "/status" => commands::handle_status(manager),
+        "/retry" => commands::handle_retry(manager, parts).await?,
+        "/refresh" => commands::handle_refresh(manager, parts).await?,
+        "/list" => commands::handle_list(manager, parts).await?,
+        "/call" => commands::handle_call(manager, input, parts).await?,
+        "/read" => commands::handle_read(manager, parts).await?,
+        "/down" => commands::handle_down(manager, parts).await?,
+        "/info" => commands::handle_info(manager, parts).await?,
+        "/use" => commands::handle_use(manager, parts).await?,
+        "/complete" => commands::handle_complete(manager, parts).await?,
+        "/newconfig" => commands::handle_newconfig(manager, parts),
+        "/config" => commands::handle_config(manager, parts),
+        "/subscribe" => commands::handle_subscribe(manager, parts).await?,
+        "/unsubscribe" => commands::handle_unsubscribe(manager, parts).await?,
+        "/subscriptions" => commands::handle_subscriptions(manager),
+        "/roots" => commands::handle_roots(manager, parts).await?,
+        "/format" => commands::handle_format(manager, parts),
+        "/exit" | "/quit" => {
+            println!("{}", "Goodbye!".cyan());
+            return Ok(false);
+        }
+        _ => commands::handle_unknown(parts[0]),
+    }
+    Ok(true)
+}
+
+/// 被 Ctrl+C 取消时提示并返回 None，其他错误原样返回
+fn report_cancelled<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if matches!(e.downcast_ref::<ClientError>(), Some(ClientError::Cancelled(_))) => {
+            println!("{}", redact(&e.to_string()).yellow());
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 命令历史保存在 ~/.mcpcsrs/history
+fn history_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".mcpcsrs").join("history"))
+}
+
+/// 打印服务器推送的通知；等待输入时在通知下方重新绘制提示符
+fn spawn_event_printer(mut events: UnboundedReceiver<ClientEvent>) {
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            console::global().print_above(&event.render());
+        }
+    });
+}
@@ -131,6 +131,7 @@ impl PromptManager {
         Ok(config.prompts.into_iter().find(|p| p.name == name))
     }
 
+    #[allow(dead_code)]
     pub fn add_prompt(&self, prompt: PromptEntry) -> anyhow::Result<()> {
         let mut config = self.load_config()?;
         
@@ -219,10 +220,10 @@ impl PromptManager {
                 for arg in &prompt.arguments {
                     let required_str = if arg.required { "required".red() } else { "optional".green() };
                     output.push_str(&format!(
-                        "    {} {} {} - {}\n",
+                        "    {} {} [{}] - {}\n",
                         "○".dimmed(),
                         arg.name.yellow(),
-                        format!("[{}]", required_str),
+                        required_str,
                         arg.description.as_deref().unwrap_or("No description")
                     ));
                 }
@@ -250,10 +251,10 @@ impl PromptManager {
             for arg in &prompt.arguments {
                 let required_str = if arg.required { "required".red() } else { "optional".green() };
                 output.push_str(&format!(
-                    "  {} {} {} ({})\n",
+                    "  {} {} [{}] ({})\n",
                     "•".blue(),
                     arg.name.yellow(),
-                    format!("[{}]", required_str),
+                    required_str,
                     arg.arg_type.cyan()
                 ));
                 if let Some(desc) = &arg.description {
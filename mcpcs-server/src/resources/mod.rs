@@ -162,6 +162,7 @@ impl ResourceManager {
         Ok(config.resources.into_iter().find(|r| r.uri == uri))
     }
 
+    #[allow(dead_code)]
     pub fn get_resource_content(&self, entry: &ResourceEntry) -> anyhow::Result<(String, Option<String>)> {
         match &entry.content {
             ResourceContent::Text { content } => {
@@ -195,10 +196,10 @@ impl ResourceManager {
             };
 
             output.push_str(&format!(
-                "{} {} {} {}\n",
+                "{} {} [{}] {}\n",
                 "•".blue(),
                 resource.name.bold(),
-                format!("[{}]", type_str),
+                type_str,
                 format!("({})", resource.uri).dimmed()
             ));
 
@@ -231,6 +232,7 @@ impl ResourceManager {
     }
 }
 
+#[allow(dead_code)]
 fn detect_mime_type(path: &Path) -> String {
     let extension = path.extension()
         .and_then(|ext| ext.to_str())
@@ -146,11 +146,9 @@ impl russh::server::Handler for SshHandler {
                     }
                 }
                 // Backspace
-                127 | 8 => {
-                    if !buffer.is_empty() {
-                        buffer.pop();
-                        session.data(channel, CryptoVec::from("\x08 \x08"))?;
-                    }
+                127 | 8 if !buffer.is_empty() => {
+                    buffer.pop();
+                    session.data(channel, CryptoVec::from("\x08 \x08"))?;
                 }
                 // Ctrl+C
                 3 => {